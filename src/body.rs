@@ -0,0 +1,113 @@
+use indexmap::IndexMap;
+use openapiv3::MediaType;
+use serde_json::Value;
+
+use crate::example::value_to_string;
+
+/// How a request body of a given media type is written with curl.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum BodyKind {
+    /// `-d` with a JSON document
+    Json,
+    /// `--data-urlencode` per field
+    UrlEncoded,
+    /// `-F` per field, with `@path` for files
+    Multipart,
+    /// `-d` with the text as-is
+    Text,
+    /// `-d` with an XML document
+    Xml,
+}
+
+impl BodyKind {
+    pub(crate) fn from_media_type(media_type: &str) -> Option<Self> {
+        let essence = media_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        match essence.as_str() {
+            "application/json" => Some(BodyKind::Json),
+            "application/x-www-form-urlencoded" => Some(BodyKind::UrlEncoded),
+            "multipart/form-data" => Some(BodyKind::Multipart),
+            "text/plain" => Some(BodyKind::Text),
+            "application/xml" | "text/xml" => Some(BodyKind::Xml),
+            essence if essence.ends_with("+json") => Some(BodyKind::Json),
+            essence if essence.ends_with("+xml") => Some(BodyKind::Xml),
+            _ => None,
+        }
+    }
+}
+
+/// Pick the media type to complete the body with.
+///
+/// The media type named by the request's `Content-Type` wins when the body defines it.
+/// Otherwise JSON is preferred, then forms, then text and XML.
+pub(crate) fn select_media_type<'a>(
+    content: &'a IndexMap<String, MediaType>,
+    content_type: Option<&str>,
+) -> Option<(&'a str, &'a MediaType, BodyKind)> {
+    if let Some(content_type) = content_type {
+        let essence = content_type.split(';').next().unwrap_or_default().trim();
+        let requested = content
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(essence));
+        if let Some((name, media_type)) = requested
+            && let Some(kind) = BodyKind::from_media_type(name)
+        {
+            return Some((name, media_type, kind));
+        }
+    }
+    [
+        BodyKind::Json,
+        BodyKind::UrlEncoded,
+        BodyKind::Multipart,
+        BodyKind::Text,
+        BodyKind::Xml,
+    ]
+    .into_iter()
+    .find_map(|kind| {
+        content
+            .iter()
+            .find(|(name, _)| BodyKind::from_media_type(name) == Some(kind))
+            .map(|(name, media_type)| (name.as_str(), media_type, kind))
+    })
+}
+
+/// Flatten an example object into form fields. Nested values are sent as JSON.
+pub(crate) fn form_fields(example: &Value) -> IndexMap<String, String> {
+    match example {
+        Value::Object(object) => object
+            .iter()
+            .map(|(name, value)| (name.to_string(), value_to_string(value)))
+            .collect(),
+        _ => IndexMap::new(),
+    }
+}
+
+/// Render an example value as an XML element called `name`.
+///
+/// Array items are repeated as sibling elements with the same name.
+pub(crate) fn to_xml(name: &str, value: &Value) -> String {
+    match value {
+        Value::Object(object) => format!(
+            "<{name}>{}</{name}>",
+            object
+                .iter()
+                .map(|(name, value)| to_xml(name, value))
+                .collect::<String>()
+        ),
+        Value::Array(items) => items.iter().map(|item| to_xml(name, item)).collect(),
+        Value::Null => format!("<{name}/>"),
+        value => format!("<{name}>{}</{name}>", escape_xml(&value_to_string(value))),
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
@@ -0,0 +1,343 @@
+use std::collections::HashSet;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+use indexmap::IndexMap;
+use openapiv3::{Components, OpenAPI};
+use serde::{Deserialize, Serialize};
+
+use crate::outline::Outline;
+use crate::reference;
+
+/// Version of what is stored in an entry. Bump it whenever the entries or the specifications
+/// stored in them change, such as when reading `$ref`s or converting Swagger 2.0 or OpenAPI 3.1
+/// gives a different result, even within a release.
+const FORMAT_VERSION: u32 = 3;
+
+/// How long after a change a file's modification time is trusted to tell it from the next
+/// change. Some filesystems only record it to the nearest 2 s.
+const MODIFIED_PRECISION: Duration = Duration::from_secs(2);
+
+/// A specification with its `$ref`s resolved, as stored in the cache.
+///
+/// Deserializing a whole specification takes time in proportion to its size, so the entry only
+/// holds what is needed before a path is matched: the outline of each path item and the
+/// security schemes. The path items and the other components follow the entry in the same
+/// file, each as JSON of its own, so only the path item that is matched and the components it
+/// references are deserialized.
+#[derive(Deserialize, Serialize)]
+struct Entry {
+    /// Version of ding that stored the entry
+    version: String,
+    /// `FORMAT_VERSION` when the entry was stored
+    format: u32,
+    /// Every file the specification was read from, the specification itself first
+    sources: Vec<Source>,
+    /// The specification without its path items, and with only its security schemes
+    spec: OpenAPI,
+    /// Where each path item is after the entry, and its outline, by its template
+    paths: IndexMap<String, (Range<usize>, Outline)>,
+    /// Where each component is after the entry, by its section and name
+    components: IndexMap<String, IndexMap<String, Range<usize>>>,
+}
+
+/// A file a cached specification was read from, as it was when the entry was stored.
+#[derive(Deserialize, Serialize)]
+struct Source {
+    path: PathBuf,
+    size: u64,
+    /// The modification time, unless the file was changed too recently to rely on it
+    modified: Option<SystemTime>,
+    /// Hash of the content, for when the size and modification time can't tell
+    hash: u64,
+}
+
+impl Source {
+    fn read(path: &Path) -> Result<Source> {
+        let metadata = std::fs::metadata(path)?;
+        let modified = metadata.modified().ok().filter(|modified| {
+            SystemTime::now()
+                .duration_since(*modified)
+                .is_ok_and(|age| age >= MODIFIED_PRECISION)
+        });
+        Ok(Source {
+            path: path.to_path_buf(),
+            size: metadata.len(),
+            modified,
+            hash: hash_file(path)?,
+        })
+    }
+
+    /// Whether the file is the same, without reading it when its size and modification time
+    /// are.
+    fn is_unchanged(&self) -> bool {
+        let Ok(metadata) = std::fs::metadata(&self.path) else {
+            return false;
+        };
+        if metadata.len() != self.size {
+            return false;
+        }
+        if self.modified.is_some() && metadata.modified().ok() == self.modified {
+            return true;
+        }
+        hash_file(&self.path).is_ok_and(|hash| hash == self.hash)
+    }
+}
+
+/// The parts of a cached specification that are only deserialized once they're needed.
+pub(crate) struct Deferred {
+    /// The whole entry file
+    content: Vec<u8>,
+    /// Where the path items and components start in `content`
+    start: usize,
+    path_items: IndexMap<String, Range<usize>>,
+    components: IndexMap<String, IndexMap<String, Range<usize>>>,
+}
+
+impl Deferred {
+    /// Add the path item at `template` to `spec`, with the components it references.
+    pub(crate) fn load(&self, spec: &mut OpenAPI, template: &str) {
+        let Some(json) = self.path_items.get(template).map(|range| self.json(range)) else {
+            return;
+        };
+        let Ok(path_item) = serde_json::from_slice(json) else {
+            return;
+        };
+        spec.paths.paths.insert(template.to_string(), path_item);
+        let Some(components) = &mut spec.components else {
+            return;
+        };
+        let mut pending = references(json);
+        let mut loaded = HashSet::new();
+        while let Some(reference) = pending.pop() {
+            if !loaded.insert(reference.clone()) {
+                continue;
+            }
+            let Some(section) = reference
+                .strip_prefix("#/components/")
+                .and_then(|reference| reference.split('/').next())
+            else {
+                continue;
+            };
+            let Some(name) = reference::component_name(&reference, section) else {
+                continue;
+            };
+            let Some(range) = self
+                .components
+                .get(section)
+                .and_then(|section| section.get(&name))
+            else {
+                continue;
+            };
+            let json = self.json(range);
+            pending.extend(references(json));
+            insert(components, section, name, json).unwrap_or_default();
+        }
+    }
+
+    fn json(&self, range: &Range<usize>) -> &[u8] {
+        &self.content[self.start + range.start..self.start + range.end]
+    }
+}
+
+/// `$XDG_CACHE_HOME/ding`, or `~/.cache/ding`.
+pub(crate) fn directory() -> Option<PathBuf> {
+    let directory = |name: &str| {
+        std::env::var_os(name)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+    };
+    directory("XDG_CACHE_HOME")
+        .or_else(|| directory("HOME").map(|home| home.join(".cache")))
+        .map(|cache| cache.join("ding"))
+}
+
+/// The specification at `spec_path` as it was stored, unless any file it was read from has
+/// changed since. It has no path items until they're loaded from the [`Deferred`] parts, only
+/// their outlines.
+pub(crate) fn load(spec_path: &Path) -> Option<(OpenAPI, IndexMap<String, Outline>, Deferred)> {
+    let content = std::fs::read(entry_path(spec_path)?).ok()?;
+    let end = content.iter().position(|&byte| byte == b'\n')?;
+    let entry: Entry = serde_json::from_slice(&content[..end]).ok()?;
+    let is_current = entry.version == env!("CARGO_PKG_VERSION")
+        && entry.format == FORMAT_VERSION
+        && entry.sources.iter().all(Source::is_unchanged);
+    if !is_current {
+        return None;
+    }
+    let mut outlines = IndexMap::with_capacity(entry.paths.len());
+    let mut path_items = IndexMap::with_capacity(entry.paths.len());
+    for (template, (range, outline)) in entry.paths {
+        outlines.insert(template.to_string(), outline);
+        path_items.insert(template, range);
+    }
+    let deferred = Deferred {
+        content,
+        start: end + 1,
+        path_items,
+        components: entry.components,
+    };
+    Some((entry.spec, outlines, deferred))
+}
+
+/// Store the specification at `spec_path`, which was read from the files in `sources`.
+pub(crate) fn store(spec_path: &Path, sources: &[PathBuf], spec: &OpenAPI) -> Result<()> {
+    let entry_path = entry_path(spec_path).context("No cache directory")?;
+    if let Some(parent) = entry_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut stored = spec.clone();
+    let mut deferred = Vec::new();
+    let mut paths = IndexMap::new();
+    for (template, path_item) in std::mem::take(&mut stored.paths.paths) {
+        let start = deferred.len();
+        serde_json::to_writer(&mut deferred, &path_item)?;
+        paths.insert(template, (start..deferred.len(), Outline::new(&path_item)));
+    }
+    let mut components = IndexMap::new();
+    if let Some(sections) = &mut stored.components {
+        let deferred = &mut deferred;
+        let components = &mut components;
+        defer("schemas", &mut sections.schemas, deferred, components)?;
+        defer("responses", &mut sections.responses, deferred, components)?;
+        defer("parameters", &mut sections.parameters, deferred, components)?;
+        defer("examples", &mut sections.examples, deferred, components)?;
+        defer(
+            "requestBodies",
+            &mut sections.request_bodies,
+            deferred,
+            components,
+        )?;
+        defer("headers", &mut sections.headers, deferred, components)?;
+        defer("links", &mut sections.links, deferred, components)?;
+        defer("callbacks", &mut sections.callbacks, deferred, components)?;
+    }
+    let entry = Entry {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        format: FORMAT_VERSION,
+        sources: sources
+            .iter()
+            .map(|source| Source::read(source))
+            .collect::<Result<_>>()?,
+        spec: stored,
+        paths,
+        components,
+    };
+    // JSON escapes line breaks in strings, so the first one ends the entry
+    let mut content = serde_json::to_vec(&entry)?;
+    content.push(b'\n');
+    content.extend(deferred);
+    // Another invocation may be reading the entry, so it's replaced in one step
+    let temporary_path = entry_path.with_extension(format!("{}.tmp", std::process::id()));
+    std::fs::write(&temporary_path, content)?;
+    std::fs::rename(&temporary_path, &entry_path)?;
+    Ok(())
+}
+
+/// Move every component of a section to the end of `deferred` as JSON, noting where each one is
+/// in `components`.
+fn defer<T: Serialize>(
+    section: &str,
+    items: &mut IndexMap<String, T>,
+    deferred: &mut Vec<u8>,
+    components: &mut IndexMap<String, IndexMap<String, Range<usize>>>,
+) -> Result<()> {
+    let mut ranges = IndexMap::new();
+    for (name, item) in items.drain(..) {
+        let start = deferred.len();
+        serde_json::to_writer(&mut *deferred, &item)?;
+        ranges.insert(name, start..deferred.len());
+    }
+    components.insert(section.to_string(), ranges);
+    Ok(())
+}
+
+/// Add a component stored as JSON to its section.
+fn insert(
+    components: &mut Components,
+    section: &str,
+    name: String,
+    json: &[u8],
+) -> serde_json::Result<()> {
+    match section {
+        "schemas" => {
+            components
+                .schemas
+                .insert(name, serde_json::from_slice(json)?);
+        }
+        "responses" => {
+            components
+                .responses
+                .insert(name, serde_json::from_slice(json)?);
+        }
+        "parameters" => {
+            components
+                .parameters
+                .insert(name, serde_json::from_slice(json)?);
+        }
+        "examples" => {
+            components
+                .examples
+                .insert(name, serde_json::from_slice(json)?);
+        }
+        "requestBodies" => {
+            components
+                .request_bodies
+                .insert(name, serde_json::from_slice(json)?);
+        }
+        "headers" => {
+            components
+                .headers
+                .insert(name, serde_json::from_slice(json)?);
+        }
+        "links" => {
+            components.links.insert(name, serde_json::from_slice(json)?);
+        }
+        "callbacks" => {
+            components
+                .callbacks
+                .insert(name, serde_json::from_slice(json)?);
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Every `#/components/...` reference in a JSON document.
+fn references(json: &[u8]) -> Vec<String> {
+    let prefix = b"\"#/components/";
+    (0..json.len())
+        .filter(|&start| json[start..].starts_with(prefix))
+        .filter_map(|start| {
+            let mut deserializer = serde_json::Deserializer::from_slice(&json[start..]);
+            String::deserialize(&mut deserializer).ok()
+        })
+        .collect()
+}
+
+/// Where the entry for the specification at `spec_path` is stored.
+fn entry_path(spec_path: &Path) -> Option<PathBuf> {
+    let spec_path = spec_path.canonicalize().ok()?;
+    let hash = hash(spec_path.as_os_str().as_encoded_bytes());
+    Some(
+        directory()?
+            .join("parsed")
+            .join(format!("{:016x}.json", hash)),
+    )
+}
+
+fn hash_file(path: &Path) -> Result<u64> {
+    Ok(hash(&std::fs::read(path)?))
+}
+
+/// The 64-bit FNV-1a hash of `bytes`.
+///
+/// Unlike the standard library's hashers it is the same across Rust versions and platforms,
+/// so entries stored by one build of ding are found by the next.
+pub(crate) fn hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
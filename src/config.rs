@@ -0,0 +1,227 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::remote;
+
+/// Name of the project configuration file, looked up from the current directory upwards.
+const PROJECT_FILE: &str = ".ding.toml";
+
+/// Environment variable that turns off reading the global and project configuration files.
+const NO_IMPLICIT_CONFIG: &str = "DING_NO_IMPLICIT_CONFIG";
+
+/// Settings read from the configuration files.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct Config {
+    /// Path, URL or registered name of the specification to use without `--spec`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) spec: Option<String>,
+    /// Prefix of the specification's paths, like `--path-prefix`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) path_prefix: Option<String>,
+    /// How results are printed without `--json` or `--widget`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) output: Option<Output>,
+    /// Headers added to commands that don't have them yet
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) headers: BTreeMap<String, String>,
+    /// Credentials by the name of the security scheme they are for
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) auth: BTreeMap<String, AuthConfig>,
+    /// Paths or URLs of specifications by a name that can be passed to `--spec`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) specs: BTreeMap<String, String>,
+    /// Settings for the hosts commands are sent to, tried in order
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) hosts: Vec<HostConfig>,
+}
+
+/// How results are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Output {
+    Text,
+    Json,
+    Widget,
+}
+
+/// Settings for commands sent to a host, which take precedence over the general ones.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct HostConfig {
+    /// The host, with a port or the start of the path when they matter, for example
+    /// `localhost:8080` or `api.example.com/orders`. `*` matches any part of the host, as in
+    /// `*.example.com`.
+    pub(crate) host: String,
+    /// Path, URL or registered name of the specification
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) spec: Option<String>,
+    /// Prefix of the specification's paths on this host, like `--path-prefix`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) path_prefix: Option<String>,
+    /// Headers added to commands for this host that don't have them yet
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) headers: BTreeMap<String, String>,
+}
+
+/// Where to find the secret for a security scheme.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct AuthConfig {
+    /// Environment variable holding the secret. Basic auth expects `username:password`.
+    pub(crate) env: String,
+    /// Insert the secret itself rather than a reference to the variable. The secret then ends
+    /// up in the command line and the shell history.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) literal: bool,
+}
+
+impl Config {
+    /// The first host entry that matches the URL of a command.
+    pub(crate) fn host(&self, url: &http::Uri) -> Option<&HostConfig> {
+        self.hosts.iter().find(|host| host.matches(url))
+    }
+
+    /// Combine with the settings of a configuration that takes precedence over this one.
+    fn merge(&mut self, other: Config) {
+        self.spec = other.spec.or(self.spec.take());
+        self.path_prefix = other.path_prefix.or(self.path_prefix.take());
+        self.output = other.output.or(self.output);
+        self.headers.extend(other.headers);
+        self.auth.extend(other.auth);
+        self.specs.extend(other.specs);
+        // The other configuration's hosts are tried first
+        let hosts = std::mem::take(&mut self.hosts);
+        self.hosts = other.hosts;
+        self.hosts.extend(hosts);
+    }
+
+    /// The secret for a security scheme, if the configuration names one.
+    ///
+    /// This is a `$NAME` reference to the environment variable for the shell to expand, unless
+    /// the scheme opts into the secret itself and the variable is set.
+    pub(crate) fn secret(&self, scheme_name: &str) -> Option<String> {
+        let auth = self.auth.get(scheme_name)?;
+        if !auth.literal {
+            return Some(format!("${}", auth.env));
+        }
+        std::env::var(&auth.env)
+            .ok()
+            .filter(|secret| !secret.is_empty())
+    }
+
+    /// The environment variables secrets are referenced through.
+    pub(crate) fn variables(&self) -> Vec<String> {
+        self.auth
+            .values()
+            .filter(|auth| !auth.literal)
+            .map(|auth| auth.env.clone())
+            .collect()
+    }
+}
+
+impl HostConfig {
+    fn matches(&self, url: &http::Uri) -> bool {
+        let Some(authority) = url.authority() else {
+            return false;
+        };
+        let (host, path) = match self.host.find('/') {
+            Some(index) => self.host.split_at(index),
+            None => (self.host.as_str(), ""),
+        };
+        let url_host = match (host.contains(':'), authority.port()) {
+            (true, Some(port)) => format!("{}:{}", authority.host(), port),
+            _ => authority.host().to_string(),
+        };
+        // The path has to start with whole segments of the prefix
+        let is_under_path = url
+            .path()
+            .strip_prefix(path)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/') || path.ends_with('/'));
+        wildcard_match(&host.to_lowercase(), &url_host.to_lowercase()) && is_under_path
+    }
+}
+
+/// Whether `text` matches `pattern`, where `*` matches any run of characters.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == text,
+        Some((prefix, rest)) => text.strip_prefix(prefix).is_some_and(|text| {
+            text.char_indices()
+                .map(|(index, _)| index)
+                .chain([text.len()])
+                .any(|index| wildcard_match(rest, &text[index..]))
+        }),
+    }
+}
+
+/// The configuration files that apply, from the one with the lowest precedence to the highest.
+///
+/// These are `~/.config/ding/config.toml` (or `$XDG_CONFIG_HOME/ding/config.toml`), the closest
+/// `.ding.toml` in the current directory or its parents, and the file passed to `--config`.
+/// With `DING_NO_IMPLICIT_CONFIG` set, only the file passed to `--config` is read.
+pub(crate) fn paths(explicit: Option<&Path>) -> Vec<PathBuf> {
+    if std::env::var_os(NO_IMPLICIT_CONFIG).is_some_and(|value| !value.is_empty()) {
+        return explicit.map(Path::to_path_buf).into_iter().collect();
+    }
+    let directory = |name: &str| {
+        std::env::var_os(name)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+    };
+    let global = directory("XDG_CONFIG_HOME")
+        .or_else(|| directory("HOME").map(|home| home.join(".config")))
+        .map(|config| config.join("ding").join("config.toml"));
+    let project = std::env::current_dir().ok().and_then(|current| {
+        current
+            .ancestors()
+            .map(|directory| directory.join(PROJECT_FILE))
+            .find(|path| path.is_file())
+    });
+    global
+        .into_iter()
+        .chain(project)
+        .filter(|path| path.is_file())
+        // An explicit file that doesn't exist is an error rather than skipped
+        .chain(explicit.map(Path::to_path_buf))
+        .collect()
+}
+
+/// Read and combine the configuration files, later ones taking precedence.
+pub(crate) fn load_all(paths: &[PathBuf]) -> Result<Config> {
+    let mut config = Config::default();
+    for path in paths {
+        let file = load(path).map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))?;
+        config.merge(file);
+    }
+    Ok(config)
+}
+
+pub(crate) fn load(path: &Path) -> Result<Config> {
+    let content = std::fs::read_to_string(path)?;
+    let mut config: Config = toml::from_str(&content)?;
+    // Paths are relative to the configuration file
+    let directory = path.parent().unwrap_or(Path::new(""));
+    for spec in config.specs.values_mut() {
+        if !remote::is_url(spec) {
+            *spec = directory.join(&*spec).to_string_lossy().to_string();
+        }
+    }
+    // Anything else is a name, which may be registered by another configuration file
+    let resolve = |spec: &mut String| {
+        let path = directory.join(&*spec);
+        if !remote::is_url(spec) && path.exists() {
+            *spec = path.to_string_lossy().to_string();
+        }
+    };
+    config.spec.iter_mut().for_each(resolve);
+    config
+        .hosts
+        .iter_mut()
+        .filter_map(|host| host.spec.as_mut())
+        .for_each(resolve);
+    Ok(config)
+}
@@ -0,0 +1,48 @@
+use indexmap::IndexMap;
+
+use crate::options;
+use crate::quote::quote;
+
+/// Cookies passed with `-b`/`--cookie`.
+#[derive(Debug, Default)]
+pub(crate) struct Cookies {
+    /// `name=value` pairs in the order they were given
+    pub(crate) values: IndexMap<String, String>,
+    /// Arguments naming a cookie file rather than cookie values
+    pub(crate) files: Vec<String>,
+}
+
+impl Cookies {
+    fn add(&mut self, argument: &str) {
+        if !argument.contains('=') {
+            self.files.push(argument.to_string());
+            return;
+        }
+        for pair in argument.split(';') {
+            if let Some((name, value)) = pair.trim().split_once('=') {
+                self.values.insert(name.to_string(), value.to_string());
+            }
+        }
+    }
+
+    /// The `-b` arguments to emit, one per cookie, with the cookies quoted by `quote_cookie`.
+    pub(crate) fn to_args(&self, quote_cookie: impl Fn(&str) -> String) -> Vec<String> {
+        self.values
+            .iter()
+            .map(|(name, value)| format!("-b {}", quote_cookie(&format!("{}={}", name, value))))
+            .chain(self.files.iter().map(|file| format!("-b {}", quote(file))))
+            .collect()
+    }
+}
+
+/// Remove every `-b`/`--cookie` argument from a curl command.
+///
+/// Returns the remaining command and the cookies that were removed.
+pub(crate) fn extract_cookies(command: &str) -> (String, Cookies) {
+    let (command, arguments) = options::extract_option(command, "-b", "--cookie");
+    let mut cookies = Cookies::default();
+    for argument in arguments {
+        cookies.add(&argument);
+    }
+    (command, cookies)
+}
@@ -0,0 +1,390 @@
+use anyhow::Result;
+use indexmap::IndexMap;
+use openapiv3::{
+    AnySchema, Components, ParameterData, ParameterSchemaOrContent, ReferenceOr, Schema,
+    SchemaKind, StringFormat, Type, VariantOrUnknownOrEmpty,
+};
+use serde_json::{Map, Value};
+
+use crate::{ReferenceOrExt, lookup_reference};
+
+/// How many `$ref`s deep the generator will follow before emitting `null`.
+const MAX_REFERENCE_DEPTH: usize = 16;
+
+/// Build a skeleton JSON value for a schema that has no example of its own.
+///
+/// Explicit examples and defaults are used when present. Otherwise objects are filled with
+/// their required properties (or every property when none are required), arrays get a single
+/// item and scalars use their first enum value or a placeholder for their format.
+pub(crate) fn example_for_schema(
+    schema: &ReferenceOr<Schema>,
+    components: &Option<Components>,
+) -> Result<Value> {
+    let mut references = Vec::new();
+    generate_reference(schema, components, &mut references)
+}
+
+/// The value the specification suggests for a parameter: its own example or first named
+/// example, or the example, default or first enum value of its schema, or a placeholder for
+/// the schema's format.
+pub(crate) fn parameter_value(
+    parameter_data: &ParameterData,
+    components: &Option<Components>,
+) -> Option<Value> {
+    if let Some(example) = &parameter_data.example {
+        return Some(example.clone());
+    }
+    let named_example = parameter_data
+        .examples
+        .values()
+        .find_map(|example| example.item(components).ok()?.value.clone());
+    if named_example.is_some() {
+        return named_example;
+    }
+    let schema = parameter_schema(parameter_data, components)?;
+    schema
+        .schema_data
+        .example
+        .clone()
+        .or_else(|| schema.schema_data.default.clone())
+        .or_else(|| enum_values(schema).into_iter().next())
+        .or_else(|| {
+            let format = match &schema.schema_kind {
+                SchemaKind::Type(Type::String(string)) => string_format_name(&string.format),
+                SchemaKind::Any(any) => any.format.as_deref(),
+                _ => None,
+            };
+            format
+                .and_then(format_placeholder)
+                .map(|placeholder| Value::String(placeholder.to_string()))
+        })
+}
+
+/// The schema of a parameter, unless it's described by `content` instead.
+pub(crate) fn parameter_schema<'a>(
+    parameter_data: &'a ParameterData,
+    components: &'a Option<Components>,
+) -> Option<&'a Schema> {
+    match &parameter_data.format {
+        ParameterSchemaOrContent::Schema(schema) => schema.item(components).ok(),
+        ParameterSchemaOrContent::Content(_) => None,
+    }
+}
+
+/// Every value listed in the schema's `enum`.
+pub(crate) fn enum_values(schema: &Schema) -> Vec<Value> {
+    match &schema.schema_kind {
+        SchemaKind::Type(Type::String(string)) => string
+            .enumeration
+            .iter()
+            .flatten()
+            .map(|value| Value::String(value.clone()))
+            .collect(),
+        SchemaKind::Type(Type::Integer(integer)) => integer
+            .enumeration
+            .iter()
+            .flatten()
+            .map(|value| Value::from(*value))
+            .collect(),
+        SchemaKind::Type(Type::Number(number)) => number
+            .enumeration
+            .iter()
+            .flatten()
+            .map(|value| Value::from(*value))
+            .collect(),
+        SchemaKind::Type(Type::Boolean(boolean)) => boolean
+            .enumeration
+            .iter()
+            .flatten()
+            .map(|value| Value::from(*value))
+            .collect(),
+        SchemaKind::Any(any) => any.enumeration.clone(),
+        _ => vec![],
+    }
+}
+
+/// Render a value the way it should appear in a URL, header or form field.
+///
+/// Strings are used as-is rather than JSON encoded, so `"owner"` becomes `owner`.
+pub(crate) fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(value) => value.clone(),
+        Value::Null => String::new(),
+        value => value.to_string(),
+    }
+}
+
+/// Names of the object's properties that hold files (`format: binary`).
+pub(crate) fn binary_properties(
+    schema: &ReferenceOr<Schema>,
+    components: &Option<Components>,
+) -> Result<Vec<String>> {
+    let schema = schema.item(components)?;
+    let properties = match &schema.schema_kind {
+        SchemaKind::Type(Type::Object(object)) => &object.properties,
+        SchemaKind::Any(any) => &any.properties,
+        _ => return Ok(vec![]),
+    };
+    let mut binary = Vec::new();
+    for (name, property) in properties.iter() {
+        let property: &Schema = match property {
+            ReferenceOr::Item(property) => property,
+            ReferenceOr::Reference { reference } => lookup_reference(reference, components)?,
+        };
+        let format = match &property.schema_kind {
+            SchemaKind::Type(Type::String(string)) => string_format_name(&string.format),
+            SchemaKind::Any(any) => any.format.as_deref(),
+            _ => None,
+        };
+        if format == Some("binary") {
+            binary.push(name.to_string());
+        }
+    }
+    Ok(binary)
+}
+
+/// Examples for every property of an object schema, required or not.
+pub(crate) fn property_examples(
+    schema: &ReferenceOr<Schema>,
+    components: &Option<Components>,
+) -> Result<Map<String, Value>> {
+    let schema = schema.item(components)?;
+    let properties = match &schema.schema_kind {
+        SchemaKind::Type(Type::Object(object)) => &object.properties,
+        SchemaKind::Any(any) => &any.properties,
+        _ => return Ok(Map::new()),
+    };
+    let mut references = Vec::new();
+    let mut examples = Map::new();
+    for (name, property) in properties.iter() {
+        examples.insert(
+            name.to_string(),
+            generate_boxed(property, components, &mut references)?,
+        );
+    }
+    Ok(examples)
+}
+
+/// Placeholder value for well known string formats.
+pub(crate) fn format_placeholder(format: &str) -> Option<&'static str> {
+    match format {
+        "date" => Some("1970-01-01"),
+        "date-time" => Some("1970-01-01T00:00:00Z"),
+        "time" => Some("00:00:00"),
+        "uuid" => Some("00000000-0000-0000-0000-000000000000"),
+        "email" => Some("user@example.com"),
+        "uri" | "url" => Some("https://example.com"),
+        "hostname" => Some("example.com"),
+        "ipv4" => Some("127.0.0.1"),
+        "ipv6" => Some("::1"),
+        _ => None,
+    }
+}
+
+/// The name of a string format as it appears in the specification.
+pub(crate) fn string_format_name(format: &VariantOrUnknownOrEmpty<StringFormat>) -> Option<&str> {
+    match format {
+        VariantOrUnknownOrEmpty::Item(StringFormat::Date) => Some("date"),
+        VariantOrUnknownOrEmpty::Item(StringFormat::DateTime) => Some("date-time"),
+        VariantOrUnknownOrEmpty::Item(StringFormat::Password) => Some("password"),
+        VariantOrUnknownOrEmpty::Item(StringFormat::Byte) => Some("byte"),
+        VariantOrUnknownOrEmpty::Item(StringFormat::Binary) => Some("binary"),
+        VariantOrUnknownOrEmpty::Unknown(format) => Some(format),
+        VariantOrUnknownOrEmpty::Empty => None,
+    }
+}
+
+fn generate_reference(
+    schema: &ReferenceOr<Schema>,
+    components: &Option<Components>,
+    references: &mut Vec<String>,
+) -> Result<Value> {
+    match schema {
+        ReferenceOr::Item(schema) => generate(schema, components, references),
+        ReferenceOr::Reference { reference } => expand(reference, components, references),
+    }
+}
+
+fn generate_boxed(
+    schema: &ReferenceOr<Box<Schema>>,
+    components: &Option<Components>,
+    references: &mut Vec<String>,
+) -> Result<Value> {
+    match schema {
+        ReferenceOr::Item(schema) => generate(schema, components, references),
+        ReferenceOr::Reference { reference } => expand(reference, components, references),
+    }
+}
+
+fn expand(
+    reference: &str,
+    components: &Option<Components>,
+    references: &mut Vec<String>,
+) -> Result<Value> {
+    // Recursive schemas (a Pet with a list of Pets) stop at the first repeat
+    if references.iter().any(|r| r == reference) || references.len() >= MAX_REFERENCE_DEPTH {
+        return Ok(Value::Null);
+    }
+    let schema: &Schema = lookup_reference(reference, components)?;
+    references.push(reference.to_string());
+    let value = generate(schema, components, references);
+    references.pop();
+    value
+}
+
+fn generate(
+    schema: &Schema,
+    components: &Option<Components>,
+    references: &mut Vec<String>,
+) -> Result<Value> {
+    if let Some(example) = &schema.schema_data.example {
+        return Ok(example.clone());
+    }
+    if let Some(default) = &schema.schema_data.default {
+        return Ok(default.clone());
+    }
+    match &schema.schema_kind {
+        SchemaKind::Type(Type::String(string)) => {
+            let value = match string.enumeration.iter().flatten().next() {
+                Some(value) => value.clone(),
+                None => string_format_name(&string.format)
+                    .and_then(format_placeholder)
+                    .unwrap_or_default()
+                    .to_string(),
+            };
+            Ok(Value::String(value))
+        }
+        SchemaKind::Type(Type::Integer(integer)) => Ok(Value::from(
+            integer
+                .enumeration
+                .iter()
+                .flatten()
+                .next()
+                .copied()
+                .or(integer.minimum)
+                .unwrap_or(0),
+        )),
+        SchemaKind::Type(Type::Number(number)) => Ok(Value::from(
+            number
+                .enumeration
+                .iter()
+                .flatten()
+                .next()
+                .copied()
+                .or(number.minimum)
+                .unwrap_or(0.0),
+        )),
+        SchemaKind::Type(Type::Boolean(boolean)) => Ok(Value::from(
+            boolean
+                .enumeration
+                .iter()
+                .flatten()
+                .next()
+                .copied()
+                .unwrap_or(false),
+        )),
+        SchemaKind::Type(Type::Object(object)) => {
+            generate_object(&object.properties, &object.required, components, references)
+        }
+        SchemaKind::Type(Type::Array(array)) => match &array.items {
+            Some(items) => Ok(Value::Array(vec![generate_boxed(
+                items, components, references,
+            )?])),
+            None => Ok(Value::Array(vec![])),
+        },
+        SchemaKind::OneOf { one_of } => generate_first(one_of, components, references),
+        SchemaKind::AnyOf { any_of } => generate_first(any_of, components, references),
+        SchemaKind::AllOf { all_of } => generate_all(all_of, components, references),
+        SchemaKind::Not { .. } => Ok(Value::Null),
+        SchemaKind::Any(any) => generate_any(any, components, references),
+    }
+}
+
+fn generate_object(
+    properties: &IndexMap<String, ReferenceOr<Box<Schema>>>,
+    required: &[String],
+    components: &Option<Components>,
+    references: &mut Vec<String>,
+) -> Result<Value> {
+    let mut object = Map::new();
+    for (name, property) in properties.iter() {
+        if !required.is_empty() && !required.contains(name) {
+            continue;
+        }
+        object.insert(
+            name.to_string(),
+            generate_boxed(property, components, references)?,
+        );
+    }
+    Ok(Value::Object(object))
+}
+
+fn generate_first(
+    schemas: &[ReferenceOr<Schema>],
+    components: &Option<Components>,
+    references: &mut Vec<String>,
+) -> Result<Value> {
+    match schemas.first() {
+        Some(schema) => generate_reference(schema, components, references),
+        None => Ok(Value::Null),
+    }
+}
+
+fn generate_all(
+    schemas: &[ReferenceOr<Schema>],
+    components: &Option<Components>,
+    references: &mut Vec<String>,
+) -> Result<Value> {
+    let mut merged = Map::new();
+    for schema in schemas {
+        match generate_reference(schema, components, references)? {
+            Value::Object(object) => merged.extend(object),
+            // allOf of non-objects can't be merged, the first concrete value wins
+            value if merged.is_empty() => return Ok(value),
+            _ => {}
+        }
+    }
+    Ok(Value::Object(merged))
+}
+
+fn generate_any(
+    any: &AnySchema,
+    components: &Option<Components>,
+    references: &mut Vec<String>,
+) -> Result<Value> {
+    if let Some(value) = any.enumeration.first() {
+        return Ok(value.clone());
+    }
+    if !any.all_of.is_empty() {
+        return generate_all(&any.all_of, components, references);
+    }
+    if !any.one_of.is_empty() {
+        return generate_first(&any.one_of, components, references);
+    }
+    if !any.any_of.is_empty() {
+        return generate_first(&any.any_of, components, references);
+    }
+    if !any.properties.is_empty() {
+        return generate_object(&any.properties, &any.required, components, references);
+    }
+    if let Some(items) = &any.items {
+        return Ok(Value::Array(vec![generate_boxed(
+            items, components, references,
+        )?]));
+    }
+    match any.typ.as_deref() {
+        Some("object") => Ok(Value::Object(Map::new())),
+        Some("array") => Ok(Value::Array(vec![])),
+        Some("string") => Ok(Value::String(
+            any.format
+                .as_deref()
+                .and_then(format_placeholder)
+                .unwrap_or_default()
+                .to_string(),
+        )),
+        Some("integer") => Ok(Value::from(any.minimum.unwrap_or(0.0) as i64)),
+        Some("number") => Ok(Value::from(any.minimum.unwrap_or(0.0))),
+        Some("boolean") => Ok(Value::Bool(false)),
+        _ => Ok(Value::Null),
+    }
+}
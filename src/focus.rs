@@ -0,0 +1,108 @@
+use crate::options::{split_words, takes_value};
+
+/// The part of a curl command the cursor is in.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Focus {
+    /// The `-X` method
+    Method,
+    /// The URL, or any other word that isn't an option
+    Url,
+    /// A `-H` header, by name
+    Header(String),
+    /// A `--data-urlencode` field, by name
+    Query(String),
+    /// A `-b` cookie, by name
+    Cookie(String),
+    /// The `-d` body, and the top-level property the cursor is in when there is one
+    Body(Option<String>),
+}
+
+/// Find what the cursor at byte offset `cursor` of `command` is editing.
+///
+/// A cursor on an option's flag counts as being on its value. Returns `None` when the cursor
+/// is between words or on an option ding doesn't complete.
+pub(crate) fn focus(command: &str, cursor: usize) -> Option<Focus> {
+    let words = split_words(command);
+    let index = words
+        .iter()
+        .position(|word| word.start <= cursor && cursor <= word.end)?;
+    let (flag, value) = if takes_value(&words[index].value) {
+        (&words[index], words.get(index + 1)?)
+    } else {
+        match index.checked_sub(1).map(|previous| &words[previous]) {
+            Some(previous) if takes_value(&previous.value) => (previous, &words[index]),
+            // `curl` itself isn't completed
+            None => return None,
+            Some(_) if words[index].value.starts_with('-') => return None,
+            Some(_) => return Some(Focus::Url),
+        }
+    };
+    // Text of the value up to the cursor, quotes included
+    let before_cursor = &command[value.start..cursor.clamp(value.start, value.end)];
+    match flag.value.as_str() {
+        "-X" | "--request" => Some(Focus::Method),
+        "-H" | "--header" => value
+            .value
+            .split_once(':')
+            .map(|(name, _)| Focus::Header(name.trim().to_string())),
+        "--data-urlencode" => {
+            let name = value.value.split_once('=').map(|(name, _)| name);
+            Some(Focus::Query(name.unwrap_or(&value.value).to_string()))
+        }
+        "-b" | "--cookie" => {
+            // Several cookies can share a flag, so pick the one the cursor is in
+            let pair = value
+                .value
+                .split(';')
+                .nth(before_cursor.matches(';').count())?;
+            let name = pair.split_once('=').map(|(name, _)| name)?;
+            Some(Focus::Cookie(name.trim().to_string()))
+        }
+        "-d" | "--data" | "--data-raw" | "--data-binary" | "--json" => {
+            Some(Focus::Body(property_at(before_cursor)))
+        }
+        _ => None,
+    }
+}
+
+/// The top-level JSON property that the end of `json` is inside of.
+fn property_at(json: &str) -> Option<String> {
+    let mut depth = 0;
+    let mut string: Option<String> = None;
+    let mut escaped = false;
+    // The last string closed at the top level, until it's followed by something
+    let mut last_string: Option<String> = None;
+    let mut property = None;
+    for c in json.chars() {
+        if let Some(current) = string.as_mut() {
+            match c {
+                _ if escaped => {
+                    current.push(c);
+                    escaped = false;
+                }
+                '\\' => escaped = true,
+                '"' => {
+                    if depth == 1 {
+                        last_string = string.take();
+                    }
+                    string = None;
+                }
+                c => current.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' => string = Some(String::new()),
+            ':' if depth == 1 => property = last_string.take(),
+            ',' if depth == 1 => property = None,
+            '{' | '[' => depth += 1,
+            '}' | ']' => depth -= 1,
+            c if c.is_whitespace() => continue,
+            _ => {}
+        }
+        if c != '"' {
+            last_string = None;
+        }
+    }
+    property
+}
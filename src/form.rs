@@ -0,0 +1,59 @@
+use indexmap::IndexMap;
+
+use crate::options;
+use crate::quote::quote;
+
+/// Form fields sent as the request body.
+#[derive(Debug, Default)]
+pub(crate) enum Form {
+    #[default]
+    None,
+    /// Sent with `--data-urlencode` as `application/x-www-form-urlencoded`
+    UrlEncoded(IndexMap<String, String>),
+    /// Sent with `-F` as `multipart/form-data`
+    Multipart(IndexMap<String, String>),
+}
+
+impl Form {
+    pub(crate) fn is_empty(&self) -> bool {
+        match self {
+            Form::None => true,
+            Form::UrlEncoded(fields) | Form::Multipart(fields) => fields.is_empty(),
+        }
+    }
+
+    /// The arguments to emit, one per field.
+    pub(crate) fn to_args(&self) -> Vec<String> {
+        match self {
+            Form::None => vec![],
+            Form::UrlEncoded(fields) => fields
+                .iter()
+                .map(|(name, value)| {
+                    format!("--data-urlencode {}", quote(&format!("{}={}", name, value)))
+                })
+                .collect(),
+            Form::Multipart(fields) => fields
+                .iter()
+                .map(|(name, value)| format!("-F {}", quote(&format!("{}={}", name, value))))
+                .collect(),
+        }
+    }
+}
+
+/// Remove every `-F`/`--form` argument from a curl command.
+///
+/// Returns the remaining command and the multipart form they described.
+pub(crate) fn extract_form(command: &str) -> (String, Form) {
+    let (command, arguments) = options::extract_option(command, "-F", "--form");
+    let fields = arguments
+        .iter()
+        .map(|argument| match argument.split_once('=') {
+            Some((name, value)) => (name.to_string(), value.to_string()),
+            None => (argument.to_string(), String::new()),
+        })
+        .collect::<IndexMap<_, _>>();
+    match fields.is_empty() {
+        true => (command, Form::None),
+        false => (command, Form::Multipart(fields)),
+    }
+}
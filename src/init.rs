@@ -0,0 +1,70 @@
+use crate::quote::quote_if_needed;
+
+/// Shells `ding init` can print a widget for.
+#[derive(clap::ValueEnum, Debug, Clone, Copy)]
+pub(crate) enum Shell {
+    Zsh,
+    Bash,
+    Fish,
+}
+
+const ZSH: &str = r#"_ding_widget() {
+    local output
+    output=$(ding --widget --cursor $CURSOR ARGS <<< "$BUFFER")
+    [[ -n $output ]] || return
+    BUFFER=${output#*$'\n'}
+    CURSOR=${output%%$'\n'*}
+}
+zle -N _ding_widget
+bindkey '^X^X' _ding_widget
+"#;
+
+const BASH: &str = r#"_ding_widget() {
+    local output
+    output=$(ding --widget --cursor "$READLINE_POINT" ARGS <<< "$READLINE_LINE")
+    [[ -n $output ]] || return
+    READLINE_LINE=${output#*$'\n'}
+    READLINE_POINT=${output%%$'\n'*}
+}
+bind -x '"\C-x\C-x": _ding_widget'
+"#;
+
+const FISH: &str = r#"function _ding_widget
+    set -l output (commandline | ding --widget --cursor (commandline -C) ARGS | string collect)
+    test -n "$output"; or return
+    set -l parts (string split -m 1 \n -- $output)
+    commandline -r -- $parts[2]
+    commandline -C -- $parts[1]
+end
+bind \cx\cx _ding_widget
+"#;
+
+/// The script that defines the widget and binds it to Ctrl-X Ctrl-X, passing `args` on to ding.
+///
+/// The widget sends the line being edited and the cursor position to ding, which prints the new
+/// cursor position on the first line of its output, followed by the new line.
+pub(crate) fn widget(shell: Shell, args: &[String]) -> String {
+    let script = match shell {
+        Shell::Zsh => ZSH,
+        Shell::Bash => BASH,
+        Shell::Fish => FISH,
+    };
+    let args = args
+        .iter()
+        .map(|arg| {
+            let quoted = quote_if_needed(arg);
+            match shell {
+                Shell::Fish if quoted != *arg => fish_quote(arg),
+                _ => quoted,
+            }
+        })
+        .map(|arg| format!(" {}", arg))
+        .collect::<String>();
+    // Without arguments the placeholder and the space before it go
+    script.replace(" ARGS", &args)
+}
+
+/// Fish escapes quotes inside single quotes with a backslash rather than `'\''`.
+fn fish_quote(word: &str) -> String {
+    format!("'{}'", word.replace('\\', "\\\\").replace('\'', "\\'"))
+}
@@ -0,0 +1,850 @@
+use indexmap::IndexMap;
+use openapiv3::{
+    AdditionalProperties, Components, OpenAPI, Operation, Parameter, ReferenceOr, Schema,
+    SchemaKind, Type,
+};
+use serde_json::Value;
+
+use crate::body::{self, BodyKind};
+use crate::config::Config;
+use crate::example;
+use crate::form::Form;
+use crate::options::CurlOptions;
+use crate::security::{self, Credential};
+use crate::{ReferenceOrExt, items, lookup_reference};
+
+/// Schemas that only refer to each other are not followed deeper than this.
+const MAX_DEPTH: usize = 64;
+
+/// A way the request differs from the operation it is sent to.
+#[derive(Debug, serde::Serialize)]
+pub(crate) struct Problem {
+    /// The kind of problem, such as `unknown-query-parameter`
+    pub(crate) rule: &'static str,
+    pub(crate) message: String,
+}
+
+fn problem(rule: &'static str, message: String) -> Problem {
+    Problem { rule, message }
+}
+
+/// The problem with input that has no curl command to check.
+pub(crate) fn no_curl_command() -> Problem {
+    problem("no-curl-command", "No curl command found".to_string())
+}
+
+/// Every way the request doesn't match the operation the specification defines for it.
+///
+/// `template` is the path the request matched, if any, and `path` the request's path, both
+/// without the path prefix. Query parameters the specification doesn't define, required
+/// parameters that are missing, parameter and body values that don't match their schemas, and
+/// bodies sent with a `Content-Type` the operation doesn't accept are reported. Without a
+/// `Content-Type` header, the type curl sends is assumed.
+pub(crate) fn lint(
+    spec: &OpenAPI,
+    config: &Config,
+    template: Option<&str>,
+    path: &str,
+    request: &curl_parser::ParsedRequest,
+    options: &CurlOptions,
+) -> Vec<Problem> {
+    let components = &spec.components;
+    let path_item = template
+        .and_then(|template| spec.paths.paths.get(template))
+        .and_then(|path_item| path_item.as_item());
+    let (Some(template), Some(path_item)) = (template, path_item) else {
+        return vec![problem(
+            "unknown-path",
+            format!("No path in the specification matches {}", path),
+        )];
+    };
+    let method = request.method.as_str();
+    let operation = path_item
+        .iter()
+        .find(|(operation_method, _)| operation_method.eq_ignore_ascii_case(method));
+    let Some((_, operation)) = operation else {
+        let methods = path_item
+            .iter()
+            .map(|(method, _)| method.to_uppercase())
+            .collect::<Vec<_>>();
+        return vec![problem(
+            "unknown-method",
+            format!(
+                "{} is not defined for {}, only {}",
+                method,
+                template,
+                methods.join(", ")
+            ),
+        )];
+    };
+
+    let mut problems = Vec::new();
+    let mut parameters: Vec<&Parameter> = Vec::new();
+    let defined = items(&path_item.parameters, components)
+        .chain(items(&operation.parameters, components))
+        .filter_map(|parameter| parameter.ok());
+    for parameter in defined {
+        // The operation's parameters override the path's ones with the same name and location
+        parameters.retain(|existing| {
+            existing.parameter_data_ref().name != parameter.parameter_data_ref().name
+                || std::mem::discriminant(*existing) != std::mem::discriminant(parameter)
+        });
+        parameters.push(parameter);
+    }
+    let query_names = parameters
+        .iter()
+        .filter(|parameter| matches!(parameter, Parameter::Query { .. }))
+        .map(|parameter| parameter.parameter_data_ref().name.to_string())
+        .chain(query_credentials(operation, spec, config))
+        .collect::<Vec<_>>();
+
+    let request_body = operation
+        .request_body
+        .as_ref()
+        .and_then(|request_body| request_body.item(components).ok());
+    let content_type = request
+        .headers
+        .get(http::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok());
+    let selected_media_type = request_body
+        .and_then(|request_body| body::select_media_type(&request_body.content, content_type));
+    let is_form_body = matches!(selected_media_type, Some((_, _, BodyKind::UrlEncoded)));
+
+    // Like ding itself, --data-urlencode fields that aren't query parameters are the form body
+    // of operations that take one
+    let mut query: IndexMap<String, Vec<String>> = IndexMap::new();
+    let mut form_fields: IndexMap<String, String> = IndexMap::new();
+    let url_query = request.url.query().unwrap_or_default();
+    for (name, value) in form_urlencoded::parse(url_query.as_bytes()) {
+        query
+            .entry(name.into_owned())
+            .or_default()
+            .push(value.into_owned());
+    }
+    for (name, value) in request.data_url_encoded.iter() {
+        if is_form_body && !query_names.contains(name) {
+            form_fields.insert(name.to_string(), value.to_string());
+        } else {
+            query
+                .entry(name.to_string())
+                .or_default()
+                .push(value.to_string());
+        }
+    }
+
+    for name in query.keys() {
+        if !query_names.contains(name) {
+            problems.push(problem(
+                "unknown-query-parameter",
+                format!("Query parameter {} is not defined for {}", name, template),
+            ));
+        }
+    }
+    let path_values = path_values(template, path);
+    for parameter in parameters {
+        let data = parameter.parameter_data_ref();
+        let (location, values) = match parameter {
+            Parameter::Query { .. } => ("query", query.get(&data.name).cloned()),
+            Parameter::Header { .. } => (
+                "header",
+                request
+                    .headers
+                    .get_all(data.name.as_str())
+                    .iter()
+                    .map(|value| value.to_str().map(str::to_string).ok())
+                    .collect::<Option<Vec<_>>>()
+                    .filter(|values| !values.is_empty()),
+            ),
+            Parameter::Cookie { .. } => (
+                "cookie",
+                options
+                    .cookies
+                    .values
+                    .get(&data.name)
+                    .map(|value| vec![value.to_string()]),
+            ),
+            Parameter::Path { .. } => (
+                "path",
+                path_values
+                    .get(&data.name)
+                    .filter(|value| !value.is_empty() && !value.starts_with('{'))
+                    .map(|value| vec![value.to_string()]),
+            ),
+        };
+        let Some(values) = values else {
+            if data.required {
+                problems.push(problem(
+                    "missing-parameter",
+                    format!("Required {} parameter {} is missing", location, data.name),
+                ));
+            }
+            continue;
+        };
+        let Some(schema) = example::parameter_schema(data, components) else {
+            continue;
+        };
+        let value = match (schema_type(schema), values.as_slice()) {
+            (Some("array"), values) => {
+                // Arrays can be given as one comma separated value or by repeating the parameter
+                let values = values.iter().flat_map(|value| value.split(',')).collect();
+                coerce_items(values, schema, components)
+            }
+            (_, [.., value]) => coerce(value, schema, components),
+            (_, []) => continue,
+        };
+        let location = format!("{} parameter {}", location, data.name);
+        let mut validator = Validator::new(components);
+        for message in validator.validate(&value, schema, &location) {
+            problems.push(problem("invalid-parameter", message));
+        }
+    }
+
+    let body = request.body();
+    let multipart_fields = match &options.form {
+        Form::Multipart(fields) => Some(fields),
+        _ => None,
+    };
+    if let Form::UrlEncoded(fields) = &options.form {
+        form_fields.extend(fields.clone());
+    }
+    let has_body = body.is_some() || !form_fields.is_empty() || multipart_fields.is_some();
+    // curl sends -F as a multipart form and -d or --data-urlencode as a url encoded one
+    let sent_content_type = match (content_type, multipart_fields) {
+        (Some(content_type), _) => Some(content_type),
+        (None, Some(_)) => Some("multipart/form-data"),
+        (None, None) if has_body => Some("application/x-www-form-urlencoded"),
+        (None, None) => None,
+    };
+    match (request_body, sent_content_type) {
+        (None, Some(_)) => problems.push(problem(
+            "unexpected-body",
+            format!("{} {} takes no request body", method, template),
+        )),
+        (Some(request_body), None) if request_body.required => problems.push(problem(
+            "missing-body",
+            format!("{} {} requires a request body", method, template),
+        )),
+        (Some(request_body), Some(sent_content_type)) => {
+            let essence = sent_content_type
+                .split(';')
+                .next()
+                .unwrap_or_default()
+                .trim();
+            let media_type = request_body
+                .content
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(essence));
+            let Some((name, media_type)) = media_type else {
+                let accepted = request_body
+                    .content
+                    .keys()
+                    .map(String::as_str)
+                    .collect::<Vec<_>>();
+                let sent = match content_type {
+                    Some(_) => format!("Content-Type {}", sent_content_type),
+                    None => format!(
+                        "Without a Content-Type header curl sends {}, which",
+                        sent_content_type
+                    ),
+                };
+                problems.push(problem(
+                    "content-type",
+                    format!(
+                        "{} is not accepted by {} {}, expected {}",
+                        sent,
+                        method,
+                        template,
+                        accepted.join(" or ")
+                    ),
+                ));
+                return problems;
+            };
+            let Some(schema) = media_type
+                .schema
+                .as_ref()
+                .and_then(|schema| schema.item(components).ok())
+            else {
+                return problems;
+            };
+            let value = match BodyKind::from_media_type(name) {
+                Some(BodyKind::Json) => {
+                    match serde_json::from_str::<Value>(&body.unwrap_or_default()) {
+                        Ok(value) => value,
+                        Err(e) => {
+                            problems.push(problem(
+                                "invalid-body",
+                                format!("body is not valid JSON: {}", e),
+                            ));
+                            return problems;
+                        }
+                    }
+                }
+                Some(BodyKind::UrlEncoded) => {
+                    let encoded = body.unwrap_or_default();
+                    let fields = form_urlencoded::parse(encoded.as_bytes())
+                        .into_owned()
+                        .chain(form_fields);
+                    form_value(fields, schema, components)
+                }
+                Some(BodyKind::Multipart) => {
+                    let fields = multipart_fields.cloned().unwrap_or_default();
+                    form_value(fields, schema, components)
+                }
+                _ => return problems,
+            };
+            let mut validator = Validator::new(components);
+            for message in validator.validate(&value, schema, "body") {
+                problems.push(problem("invalid-body", message));
+            }
+        }
+        _ => {}
+    }
+    problems
+}
+
+/// Names of the query parameters the operation's security schemes send credentials in.
+fn query_credentials(operation: &Operation, spec: &OpenAPI, config: &Config) -> Vec<String> {
+    security::credentials(operation, spec, config)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|credential| match credential {
+            Credential::Query { name, .. } => Some(name),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The values of the path parameters in `path`, by name.
+///
+/// Only segments that are a single parameter, like `{petId}`, are matched.
+fn path_values(template: &str, path: &str) -> IndexMap<String, String> {
+    template
+        .split('/')
+        .zip(path.split('/'))
+        .filter_map(|(template, value)| {
+            let name = template.strip_prefix('{')?.strip_suffix('}')?;
+            Some((name.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// An object of form fields, with each value converted to the type of its property.
+fn form_value(
+    fields: impl IntoIterator<Item = (String, String)>,
+    schema: &Schema,
+    components: &Option<Components>,
+) -> Value {
+    let properties = match &schema.schema_kind {
+        SchemaKind::Type(Type::Object(object)) => Some(&object.properties),
+        SchemaKind::Any(any) => Some(&any.properties),
+        _ => None,
+    };
+    let object = fields
+        .into_iter()
+        .map(|(name, value)| {
+            let property = properties
+                .and_then(|properties| properties.get(&name))
+                .and_then(|property| resolve_boxed(property, components));
+            let value = match property {
+                Some(property) => coerce(&value, property, components),
+                None => Value::String(value),
+            };
+            (name, value)
+        })
+        .collect();
+    Value::Object(object)
+}
+
+/// The JSON value text stands for, given the type its schema expects.
+///
+/// Text that isn't of that type is kept as a string, so validation reports it.
+fn coerce(text: &str, schema: &Schema, components: &Option<Components>) -> Value {
+    match schema_type(schema) {
+        Some("integer") => text.parse::<i64>().map(Value::from).ok(),
+        Some("number") => text
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number),
+        Some("boolean") => text.parse::<bool>().map(Value::Bool).ok(),
+        Some("array") => Some(coerce_items(text.split(',').collect(), schema, components)),
+        _ => None,
+    }
+    .unwrap_or_else(|| Value::String(text.to_string()))
+}
+
+fn coerce_items(items: Vec<&str>, schema: &Schema, components: &Option<Components>) -> Value {
+    let item_schema = match &schema.schema_kind {
+        SchemaKind::Type(Type::Array(array)) => array.items.as_ref(),
+        SchemaKind::Any(any) => any.items.as_ref(),
+        _ => None,
+    }
+    .and_then(|items| resolve_boxed(items, components));
+    let items = items
+        .into_iter()
+        .map(|item| match item_schema {
+            Some(item_schema) => coerce(item, item_schema, components),
+            None => Value::String(item.to_string()),
+        })
+        .collect();
+    Value::Array(items)
+}
+
+fn schema_type(schema: &Schema) -> Option<&str> {
+    match &schema.schema_kind {
+        SchemaKind::Type(Type::String(_)) => Some("string"),
+        SchemaKind::Type(Type::Number(_)) => Some("number"),
+        SchemaKind::Type(Type::Integer(_)) => Some("integer"),
+        SchemaKind::Type(Type::Boolean(_)) => Some("boolean"),
+        SchemaKind::Type(Type::Object(_)) => Some("object"),
+        SchemaKind::Type(Type::Array(_)) => Some("array"),
+        SchemaKind::Any(any) => any.typ.as_deref(),
+        _ => None,
+    }
+}
+
+fn resolve_boxed<'a>(
+    schema: &'a ReferenceOr<Box<Schema>>,
+    components: &'a Option<Components>,
+) -> Option<&'a Schema> {
+    match schema {
+        ReferenceOr::Item(schema) => Some(schema),
+        ReferenceOr::Reference { reference } => lookup_reference(reference, components).ok(),
+    }
+}
+
+/// Checks values against schemas. `pattern` and `format` aren't checked.
+struct Validator<'a> {
+    components: &'a Option<Components>,
+    depth: usize,
+}
+
+impl<'a> Validator<'a> {
+    fn new(components: &'a Option<Components>) -> Self {
+        Validator {
+            components,
+            depth: 0,
+        }
+    }
+
+    /// Messages for every way `value` doesn't match `schema`, naming where as `location`.
+    fn validate(&mut self, value: &Value, schema: &Schema, location: &str) -> Vec<String> {
+        if value.is_null() && schema.schema_data.nullable {
+            return vec![];
+        }
+        if self.depth > MAX_DEPTH {
+            return vec![];
+        }
+        self.depth += 1;
+        let messages = self.validate_kind(value, &schema.schema_kind, location);
+        self.depth -= 1;
+        messages
+    }
+
+    fn validate_kind(&mut self, value: &Value, kind: &SchemaKind, location: &str) -> Vec<String> {
+        let mut messages = Vec::new();
+        match kind {
+            SchemaKind::Type(Type::String(string)) => {
+                let Some(text) = value.as_str() else {
+                    return vec![type_message(location, "a string", value)];
+                };
+                let allowed = string.enumeration.iter().flatten();
+                if allowed.clone().next().is_some()
+                    && !allowed.clone().any(|allowed| allowed == text)
+                {
+                    messages.push(enum_message(
+                        location,
+                        allowed.map(|allowed| allowed.as_str().into()),
+                    ));
+                }
+                check_length(
+                    location,
+                    text,
+                    string.min_length,
+                    string.max_length,
+                    &mut messages,
+                );
+            }
+            SchemaKind::Type(Type::Integer(integer)) => {
+                let Some(number) = value.as_i64() else {
+                    return vec![type_message(location, "an integer", value)];
+                };
+                let allowed = integer.enumeration.iter().flatten();
+                if allowed.clone().next().is_some()
+                    && !allowed.clone().any(|allowed| *allowed == number)
+                {
+                    messages.push(enum_message(
+                        location,
+                        allowed.map(|allowed| (*allowed).into()),
+                    ));
+                }
+                check_bounds(
+                    location,
+                    number as f64,
+                    (
+                        integer.minimum.map(|minimum| minimum as f64),
+                        integer.exclusive_minimum,
+                    ),
+                    (
+                        integer.maximum.map(|maximum| maximum as f64),
+                        integer.exclusive_maximum,
+                    ),
+                    &mut messages,
+                );
+            }
+            SchemaKind::Type(Type::Number(number)) => {
+                let Some(value) = value.as_f64() else {
+                    return vec![type_message(location, "a number", value)];
+                };
+                let allowed = number.enumeration.iter().flatten();
+                if allowed.clone().next().is_some()
+                    && !allowed.clone().any(|allowed| *allowed == value)
+                {
+                    messages.push(enum_message(
+                        location,
+                        allowed.map(|allowed| (*allowed).into()),
+                    ));
+                }
+                check_bounds(
+                    location,
+                    value,
+                    (number.minimum, number.exclusive_minimum),
+                    (number.maximum, number.exclusive_maximum),
+                    &mut messages,
+                );
+            }
+            SchemaKind::Type(Type::Boolean(boolean)) => {
+                let Some(value) = value.as_bool() else {
+                    return vec![type_message(location, "a boolean", value)];
+                };
+                let allowed = boolean.enumeration.iter().flatten();
+                if allowed.clone().next().is_some()
+                    && !allowed.clone().any(|allowed| *allowed == value)
+                {
+                    messages.push(enum_message(
+                        location,
+                        allowed.map(|allowed| (*allowed).into()),
+                    ));
+                }
+            }
+            SchemaKind::Type(Type::Object(object)) => {
+                if !value.is_object() {
+                    return vec![type_message(location, "an object", value)];
+                }
+                messages.extend(self.validate_object(
+                    value,
+                    &object.properties,
+                    &object.required,
+                    object.additional_properties.as_ref(),
+                    location,
+                ));
+            }
+            SchemaKind::Type(Type::Array(array)) => {
+                let Some(values) = value.as_array() else {
+                    return vec![type_message(location, "an array", value)];
+                };
+                messages.extend(self.validate_items(values, array.items.as_ref(), location));
+                check_count(
+                    location,
+                    values.len(),
+                    array.min_items,
+                    array.max_items,
+                    &mut messages,
+                );
+            }
+            SchemaKind::AllOf { all_of } => {
+                for schema in all_of {
+                    messages.extend(self.validate_reference(value, schema, location));
+                }
+            }
+            SchemaKind::AnyOf { any_of } => {
+                let matching = self.count_matching(value, any_of, location);
+                if matching == 0 {
+                    messages.push(format!(
+                        "{} doesn't match any of its anyOf schemas",
+                        location
+                    ));
+                }
+            }
+            SchemaKind::OneOf { one_of } => {
+                let matching = self.count_matching(value, one_of, location);
+                if matching != 1 {
+                    messages.push(format!(
+                        "{} matches {} of its oneOf schemas instead of one",
+                        location, matching
+                    ));
+                }
+            }
+            SchemaKind::Not { not } => {
+                if self.validate_reference(value, not, location).is_empty() {
+                    messages.push(format!("{} matches the schema it must not match", location));
+                }
+            }
+            SchemaKind::Any(any) => {
+                let expected = match any.typ.as_deref() {
+                    Some("string") if !value.is_string() => Some("a string"),
+                    Some("integer") if !value.is_i64() && !value.is_u64() => Some("an integer"),
+                    Some("number") if !value.is_number() => Some("a number"),
+                    Some("boolean") if !value.is_boolean() => Some("a boolean"),
+                    Some("object") if !value.is_object() => Some("an object"),
+                    Some("array") if !value.is_array() => Some("an array"),
+                    _ => None,
+                };
+                if let Some(expected) = expected {
+                    return vec![type_message(location, expected, value)];
+                }
+                if !any.enumeration.is_empty() && !any.enumeration.contains(value) {
+                    messages.push(enum_message(location, any.enumeration.iter().cloned()));
+                }
+                if let Some(text) = value.as_str() {
+                    check_length(
+                        location,
+                        text,
+                        any.min_length,
+                        any.max_length,
+                        &mut messages,
+                    );
+                }
+                if let Some(number) = value.as_f64() {
+                    check_bounds(
+                        location,
+                        number,
+                        (any.minimum, any.exclusive_minimum.unwrap_or_default()),
+                        (any.maximum, any.exclusive_maximum.unwrap_or_default()),
+                        &mut messages,
+                    );
+                }
+                if value.is_object() {
+                    messages.extend(self.validate_object(
+                        value,
+                        &any.properties,
+                        &any.required,
+                        any.additional_properties.as_ref(),
+                        location,
+                    ));
+                }
+                if let Some(values) = value.as_array() {
+                    messages.extend(self.validate_items(values, any.items.as_ref(), location));
+                    check_count(
+                        location,
+                        values.len(),
+                        any.min_items,
+                        any.max_items,
+                        &mut messages,
+                    );
+                }
+                for schema in any.all_of.iter() {
+                    messages.extend(self.validate_reference(value, schema, location));
+                }
+                if !any.any_of.is_empty() && self.count_matching(value, &any.any_of, location) == 0
+                {
+                    messages.push(format!(
+                        "{} doesn't match any of its anyOf schemas",
+                        location
+                    ));
+                }
+                if !any.one_of.is_empty() {
+                    let matching = self.count_matching(value, &any.one_of, location);
+                    if matching != 1 {
+                        messages.push(format!(
+                            "{} matches {} of its oneOf schemas instead of one",
+                            location, matching
+                        ));
+                    }
+                }
+            }
+        }
+        messages
+    }
+
+    fn validate_object(
+        &mut self,
+        value: &Value,
+        properties: &IndexMap<String, ReferenceOr<Box<Schema>>>,
+        required: &[String],
+        additional_properties: Option<&AdditionalProperties>,
+        location: &str,
+    ) -> Vec<String> {
+        let Some(object) = value.as_object() else {
+            return vec![];
+        };
+        let mut messages = Vec::new();
+        for name in required {
+            if !object.contains_key(name) {
+                messages.push(format!(
+                    "{} is missing required property {}",
+                    location, name
+                ));
+            }
+        }
+        for (name, value) in object {
+            let property_location = format!("{}.{}", location, name);
+            match properties.get(name) {
+                Some(property) => {
+                    if let Some(property) = resolve_boxed(property, self.components) {
+                        messages.extend(self.validate(value, property, &property_location));
+                    }
+                }
+                None => match additional_properties {
+                    Some(AdditionalProperties::Any(false)) => messages.push(format!(
+                        "{} has property {}, which its schema doesn't define",
+                        location, name
+                    )),
+                    Some(AdditionalProperties::Schema(schema)) => {
+                        messages.extend(self.validate_reference(value, schema, &property_location));
+                    }
+                    _ => {}
+                },
+            }
+        }
+        messages
+    }
+
+    fn validate_items(
+        &mut self,
+        values: &[Value],
+        items: Option<&ReferenceOr<Box<Schema>>>,
+        location: &str,
+    ) -> Vec<String> {
+        let Some(items) = items.and_then(|items| resolve_boxed(items, self.components)) else {
+            return vec![];
+        };
+        values
+            .iter()
+            .enumerate()
+            .flat_map(|(index, value)| {
+                self.validate(value, items, &format!("{}[{}]", location, index))
+            })
+            .collect()
+    }
+
+    fn validate_reference(
+        &mut self,
+        value: &Value,
+        schema: &ReferenceOr<Schema>,
+        location: &str,
+    ) -> Vec<String> {
+        match schema.item(self.components) {
+            Ok(schema) => self.validate(value, schema, location),
+            Err(_) => vec![],
+        }
+    }
+
+    fn count_matching(
+        &mut self,
+        value: &Value,
+        schemas: &[ReferenceOr<Schema>],
+        location: &str,
+    ) -> usize {
+        schemas
+            .iter()
+            .filter(|schema| self.validate_reference(value, schema, location).is_empty())
+            .count()
+    }
+}
+
+fn type_message(location: &str, expected: &str, value: &Value) -> String {
+    let actual = match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(number) if number.is_f64() => "a number",
+        Value::Number(_) => "an integer",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    };
+    format!(
+        "{} must be {}, not {} ({})",
+        location, expected, actual, value
+    )
+}
+
+fn enum_message(location: &str, allowed: impl Iterator<Item = Value>) -> String {
+    let allowed = allowed
+        .map(|allowed| allowed.to_string())
+        .collect::<Vec<_>>();
+    format!("{} must be one of {}", location, allowed.join(", "))
+}
+
+fn check_length(
+    location: &str,
+    text: &str,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    messages: &mut Vec<String>,
+) {
+    let length = text.chars().count();
+    if let Some(min_length) = min_length
+        && length < min_length
+    {
+        messages.push(format!(
+            "{} must be at least {} characters long",
+            location, min_length
+        ));
+    }
+    if let Some(max_length) = max_length
+        && length > max_length
+    {
+        messages.push(format!(
+            "{} must be at most {} characters long",
+            location, max_length
+        ));
+    }
+}
+
+fn check_count(
+    location: &str,
+    count: usize,
+    min_items: Option<usize>,
+    max_items: Option<usize>,
+    messages: &mut Vec<String>,
+) {
+    if let Some(min_items) = min_items
+        && count < min_items
+    {
+        messages.push(format!(
+            "{} must have at least {} items",
+            location, min_items
+        ));
+    }
+    if let Some(max_items) = max_items
+        && count > max_items
+    {
+        messages.push(format!(
+            "{} must have at most {} items",
+            location, max_items
+        ));
+    }
+}
+
+/// Check a number against its `(bound, is_exclusive)` minimum and maximum.
+fn check_bounds(
+    location: &str,
+    number: f64,
+    (minimum, exclusive_minimum): (Option<f64>, bool),
+    (maximum, exclusive_maximum): (Option<f64>, bool),
+    messages: &mut Vec<String>,
+) {
+    if let Some(minimum) = minimum {
+        match exclusive_minimum {
+            true if number <= minimum => {
+                messages.push(format!("{} must be greater than {}", location, minimum))
+            }
+            false if number < minimum => {
+                messages.push(format!("{} must be at least {}", location, minimum))
+            }
+            _ => {}
+        }
+    }
+    if let Some(maximum) = maximum {
+        match exclusive_maximum {
+            true if number >= maximum => {
+                messages.push(format!("{} must be less than {}", location, maximum))
+            }
+            false if number > maximum => {
+                messages.push(format!("{} must be at most {}", location, maximum))
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,296 @@
+use std::fmt;
+use std::path::Path;
+
+use openapiv3::{
+    Callback, Example, Header, Link, OpenAPI, Operation, Parameter, PathItem, RequestBody,
+    Response, Schema, SecurityScheme,
+};
+use serde::Deserialize;
+use serde::de::{DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde_yaml::Value;
+
+use crate::reference;
+
+/// The parts of a specification that are parsed on their own to find a problem.
+#[derive(Clone, Copy)]
+enum Part {
+    OpenApi,
+    PathItem,
+    Operation,
+    Callback,
+    Parameter,
+    RequestBody,
+    Response,
+    Header,
+    Schema,
+    Example,
+    SecurityScheme,
+    Link,
+}
+
+impl Part {
+    /// Where the parts inside this one are, `*` standing for every key or item.
+    fn parts(self) -> &'static [(&'static str, Part)] {
+        match self {
+            Part::OpenApi => &[
+                ("paths/*", Part::PathItem),
+                ("components/schemas/*", Part::Schema),
+                ("components/responses/*", Part::Response),
+                ("components/parameters/*", Part::Parameter),
+                ("components/examples/*", Part::Example),
+                ("components/requestBodies/*", Part::RequestBody),
+                ("components/headers/*", Part::Header),
+                ("components/securitySchemes/*", Part::SecurityScheme),
+                ("components/links/*", Part::Link),
+                ("components/callbacks/*", Part::Callback),
+            ],
+            Part::PathItem => &[
+                ("parameters/*", Part::Parameter),
+                ("get", Part::Operation),
+                ("put", Part::Operation),
+                ("post", Part::Operation),
+                ("delete", Part::Operation),
+                ("options", Part::Operation),
+                ("head", Part::Operation),
+                ("patch", Part::Operation),
+                ("trace", Part::Operation),
+            ],
+            Part::Operation => &[
+                ("parameters/*", Part::Parameter),
+                ("requestBody", Part::RequestBody),
+                ("responses/*", Part::Response),
+                ("callbacks/*", Part::Callback),
+            ],
+            Part::Callback => &[("*", Part::PathItem)],
+            Part::Parameter => &[
+                ("schema", Part::Schema),
+                ("content/*/schema", Part::Schema),
+                ("examples/*", Part::Example),
+            ],
+            Part::RequestBody => &[
+                ("content/*/schema", Part::Schema),
+                ("content/*/examples/*", Part::Example),
+            ],
+            Part::Response => &[
+                ("headers/*", Part::Header),
+                ("content/*/schema", Part::Schema),
+                ("content/*/examples/*", Part::Example),
+                ("links/*", Part::Link),
+            ],
+            Part::Header => &[("schema", Part::Schema)],
+            Part::Schema => &[
+                ("properties/*", Part::Schema),
+                ("items", Part::Schema),
+                ("not", Part::Schema),
+                ("allOf/*", Part::Schema),
+                ("anyOf/*", Part::Schema),
+                ("oneOf/*", Part::Schema),
+            ],
+            Part::Example | Part::SecurityScheme | Part::Link => &[],
+        }
+    }
+
+    fn parse<'de, D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        match self {
+            Part::OpenApi => OpenAPI::deserialize(deserializer).map(drop),
+            Part::PathItem => PathItem::deserialize(deserializer).map(drop),
+            Part::Operation => Operation::deserialize(deserializer).map(drop),
+            Part::Callback => Callback::deserialize(deserializer).map(drop),
+            Part::Parameter => Parameter::deserialize(deserializer).map(drop),
+            Part::RequestBody => RequestBody::deserialize(deserializer).map(drop),
+            Part::Response => Response::deserialize(deserializer).map(drop),
+            Part::Header => Header::deserialize(deserializer).map(drop),
+            Part::Schema => Schema::deserialize(deserializer).map(drop),
+            Part::Example => Example::deserialize(deserializer).map(drop),
+            Part::SecurityScheme => SecurityScheme::deserialize(deserializer).map(drop),
+            Part::Link => Link::deserialize(deserializer).map(drop),
+        }
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for Part {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        self.parse(deserializer)
+    }
+}
+
+/// Where in a specification's text it stops being an OpenAPI document, and why.
+///
+/// Most of a specification can be a `$ref` instead, and the typed parse only tells that
+/// neither fits, at the start of the part that contains the problem. So the innermost part
+/// that doesn't parse is found first, and then parsed on its own from the text.
+pub(crate) fn locate(content: &str, path: &Path) -> Option<String> {
+    let document = reference::parse(content, path).ok()?;
+    if Part::OpenApi.parse(document.clone()).is_ok() {
+        return None;
+    }
+    let (segments, part) = innermost(&document, Part::OpenApi, Vec::new());
+    let error = reference::parse_seed(content, path, At::new(&segments, part)).err()?;
+    // YAML errors start with the path to the problem, though it stops short at parts that are
+    // buffered. JSON errors have none.
+    let location = Segments(&segments).to_string();
+    let message = match error.message().split_once(": ") {
+        _ if location.is_empty() => error.message().to_string(),
+        Some((prefix, _)) if prefix.starts_with(&location) => error.message().to_string(),
+        Some((prefix, message)) if location.starts_with(prefix) => {
+            format!("{}: {}", location, message)
+        }
+        _ => format!("{}: {}", location, error.message()),
+    };
+    Some(match error.position() {
+        Some((line, column)) => format!("{} at line {} column {}", message, line, column),
+        None => message,
+    })
+}
+
+/// The innermost part of `value` that doesn't parse, and the keys and indexes leading to it.
+fn innermost(value: &Value, part: Part, segments: Vec<String>) -> (Vec<String>, Part) {
+    for (pattern, inner) in part.parts() {
+        for (inner_segments, inner_value) in matches(value, pattern) {
+            let is_reference = inner_value.get("$ref").is_some();
+            if !is_reference && inner.parse(inner_value.clone()).is_err() {
+                let segments = segments.iter().cloned().chain(inner_segments).collect();
+                return innermost(inner_value, *inner, segments);
+            }
+        }
+    }
+    (segments, part)
+}
+
+fn matches<'a>(value: &'a Value, pattern: &str) -> Vec<(Vec<String>, &'a Value)> {
+    let mut found = vec![(Vec::new(), value)];
+    for segment in pattern.split('/') {
+        found = found
+            .into_iter()
+            .flat_map(|(segments, value)| {
+                children(value, segment)
+                    .into_iter()
+                    .map(move |(key, child)| {
+                        let mut segments = segments.clone();
+                        segments.push(key);
+                        (segments, child)
+                    })
+            })
+            .collect();
+    }
+    found
+}
+
+/// The children of `value` that `segment` matches, items by their index in brackets.
+fn children<'a>(value: &'a Value, segment: &str) -> Vec<(String, &'a Value)> {
+    match (value, segment) {
+        (Value::Mapping(mapping), "*") => mapping
+            .iter()
+            .filter_map(|(key, child)| Some((key_text(key)?, child)))
+            .collect(),
+        (Value::Sequence(items), "*") => items
+            .iter()
+            .enumerate()
+            .map(|(index, child)| (format!("[{}]", index), child))
+            .collect(),
+        (Value::Mapping(mapping), key) => mapping
+            .get(key)
+            .map(|child| (key.to_string(), child))
+            .into_iter()
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn key_text(key: &Value) -> Option<String> {
+    match key {
+        Value::String(key) => Some(key.to_string()),
+        Value::Number(key) => Some(key.to_string()),
+        Value::Bool(key) => Some(key.to_string()),
+        _ => None,
+    }
+}
+
+/// Keys and indexes written like `paths./pets.get.parameters[0]`.
+struct Segments<'a>(&'a [String]);
+
+impl fmt::Display for Segments<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.0.iter().enumerate() {
+            if i > 0 && !segment.starts_with('[') {
+                write!(f, ".")?;
+            }
+            write!(f, "{}", segment)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses the value at the end of `segments` with `seed`, skipping over everything else.
+struct At<'a, S> {
+    segments: &'a [String],
+    seed: S,
+}
+
+impl<'a, S> At<'a, S> {
+    fn new(segments: &'a [String], seed: S) -> Self {
+        At { segments, seed }
+    }
+}
+
+impl<'de, S: DeserializeSeed<'de, Value = ()>> DeserializeSeed<'de> for At<'_, S> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        match self.segments.is_empty() {
+            true => self.seed.deserialize(deserializer),
+            false => deserializer.deserialize_any(self),
+        }
+    }
+}
+
+impl<'de, S: DeserializeSeed<'de, Value = ()>> Visitor<'de> for At<'_, S> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a mapping or a sequence")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        let (segment, segments) = self.segments.split_first().expect("not the end");
+        let mut seed = Some(self.seed);
+        while let Some(key) = map.next_key::<Key>()? {
+            match seed.take_if(|_| key.0.as_deref() == Some(segment.as_str())) {
+                Some(seed) => map.next_value_seed(At::new(segments, seed))?,
+                None => map.next_value::<IgnoredAny>().map(drop)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        let (segment, segments) = self.segments.split_first().expect("not the end");
+        let index = segment
+            .strip_prefix('[')
+            .and_then(|segment| segment.strip_suffix(']'))
+            .and_then(|index| index.parse::<usize>().ok());
+        let mut seed = Some(self.seed);
+        let mut i = 0;
+        loop {
+            let found = match seed.take_if(|_| Some(i) == index) {
+                Some(seed) => seq.next_element_seed(At::new(segments, seed))?,
+                None => seq.next_element::<IgnoredAny>()?.map(drop),
+            };
+            if found.is_none() {
+                return Ok(());
+            }
+            i += 1;
+        }
+    }
+}
+
+/// A mapping key as text, when it's a scalar.
+struct Key(Option<String>);
+
+impl<'de> Deserialize<'de> for Key {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Value::deserialize(deserializer).map(|key| Key(key_text(&key)))
+    }
+}
@@ -1,19 +1,53 @@
 use anyhow::Result;
-use clap::Parser;
+use clap::{CommandFactory, Parser, Subcommand};
 use indexmap::IndexMap;
-use openapiv3::{Components, Parameter, ReferenceOr, RequestBody, Response, Schema};
+use openapiv3::{
+    Components, Example, Parameter, ReferenceOr, RequestBody, Response, Schema, SecurityScheme,
+};
 use std::collections::BTreeMap;
 use std::io::{self, Read, Write};
-use std::path::PathBuf;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
+mod body;
+mod cache;
+mod config;
+mod cookie;
+mod example;
+mod focus;
+mod form;
+mod init;
+mod lint;
+mod locate;
+mod openapi31;
+mod options;
+mod outline;
+mod path_completion;
+mod quote;
+mod reference;
+mod remote;
+mod rewrite;
+mod security;
+mod server;
+mod shell;
+mod swagger;
+
 /// A command line tool that processes OpenAPI specifications
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(author, version, about, long_about = None, subcommand_negates_reqs = true)]
 struct Args {
-    /// Path to the OpenAPI specification file
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Path or URL of the OpenAPI specification file
+    ///
+    /// URLs are fetched once and cached, and fetched again only when the server reports a new
+    /// version. The name of a specification registered under `[specs]` in the configuration
+    /// file can be given instead. Without it, the specification configured for the command's
+    /// host under `[[hosts]]`, or the configuration's `spec`, is used.
     #[arg(short, long, value_name = "FILE")]
-    spec: PathBuf,
+    spec: Option<PathBuf>,
 
     /// Print suggested cursor position.
     ///
@@ -21,6 +55,21 @@ struct Args {
     #[arg(short, long)]
     json: bool,
 
+    /// Print the suggested cursor position on the first line, followed by the output
+    ///
+    /// This is what the shell widgets from `ding init` read.
+    #[arg(long, conflicts_with = "json")]
+    widget: bool,
+
+    /// Print every candidate completion instead of picking one
+    ///
+    /// Each candidate is a path, method, server, parameter or enum value with a label, a
+    /// description and the command and cursor position it completes to. With `--json` they are
+    /// printed as a JSON array, otherwise as NUL separated records of tab separated label,
+    /// description, cursor position and command, which `fzf --read0` can read.
+    #[arg(long, conflicts_with = "widget")]
+    candidates: bool,
+
     /// Optional prefix added to paths in the OpenAPI specification
     ///
     /// This is helpful when the OpenAPI spec is not at the root of the host. This prefix MUST
@@ -28,117 +77,550 @@ struct Args {
     #[arg(short, long)]
     path_prefix: Option<String>,
 
+    /// Server used to complete commands that don't have a host, by index or description
+    ///
+    /// Defaults to the first server that applies to the path. The server's base path is also
+    /// stripped from URLs when matching them against the specification.
+    #[arg(long, value_name = "SERVER")]
+    server: Option<String>,
+
+    /// Path to a configuration file
+    ///
+    /// The configuration names the environment variables holding the secrets for each security
+    /// scheme, for example `[auth.bearerAuth]` with `env = "API_TOKEN"`, registers specifications
+    /// by name under `[specs]` and picks them by host under `[[hosts]]`. It takes precedence
+    /// over `~/.config/ding/config.toml` and the closest `.ding.toml`, which are read unless
+    /// `DING_NO_IMPLICIT_CONFIG` is set.
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// Fill every required parameter at once instead of one per invocation
+    #[arg(long, conflicts_with = "all")]
+    all_required: bool,
+
+    /// Fill every parameter at once instead of one per invocation
+    #[arg(long)]
+    all: bool,
+
+    /// Character offset of the cursor in the input
+    ///
+    /// Only the element under the cursor (the method, the URL, a header, a `--data-urlencode`
+    /// field, a cookie or a body property) is completed or cycled. Without it, or when the
+    /// cursor is at the end of the command, the next missing element is completed.
+    #[arg(long, value_name = "OFFSET")]
+    cursor: Option<usize>,
+
     /// Read input from stdin if provided
     #[arg(hide = true)]
     stdin_input: Option<String>,
     // TODO probably add an option so you can specify a prefix on the paths
 }
 
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Print a key binding that runs ding on the command line being edited
+    ///
+    /// Add `eval "$(ding init zsh -- --spec path/to/openapi.yaml)"` to `~/.zshrc`, or the
+    /// equivalent for bash or fish. Arguments after `--` are passed to ding by the widget.
+    Init {
+        shell: init::Shell,
+
+        /// Arguments the widget passes to ding, such as `--spec`
+        #[arg(last = true)]
+        args: Vec<String>,
+    },
+    /// Check a curl command against the specification instead of completing it
+    ///
+    /// Query parameters the operation doesn't define, missing required parameters, parameter
+    /// and body values that don't match their schemas, and bodies sent with a Content-Type the
+    /// operation doesn't accept are printed, one per line. Exits with 2 when there are any, so
+    /// it can be used in CI.
+    Lint,
+    /// Inspect the configuration
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigCommand {
+    /// Print the configuration in effect, combined from every file that applies and the flags
+    Show,
+}
+
+/// How the result is printed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OutputFormat {
+    /// Only the rewritten input
+    Text,
+    /// `OutputMetadata` as JSON
+    Json,
+    /// The cursor position on the first line, then the rewritten input
+    Widget,
+    /// Every `Candidate` as a NUL separated record
+    Candidates,
+    /// Every `Candidate` as a JSON array
+    JsonCandidates,
+}
+
+impl OutputFormat {
+    /// The format the flags ask for, or else the configuration's `output`.
+    fn new(args: &Args, output: Option<config::Output>) -> Self {
+        match (flag_output(args).or(output), args.candidates) {
+            (Some(config::Output::Json), true) => OutputFormat::JsonCandidates,
+            (_, true) => OutputFormat::Candidates,
+            (Some(config::Output::Json), false) => OutputFormat::Json,
+            (Some(config::Output::Widget), false) => OutputFormat::Widget,
+            (Some(config::Output::Text) | None, false) => OutputFormat::Text,
+        }
+    }
+
+    fn is_candidates(self) -> bool {
+        matches!(
+            self,
+            OutputFormat::Candidates | OutputFormat::JsonCandidates
+        )
+    }
+}
+
+/// The output style `--json` or `--widget` asks for.
+fn flag_output(args: &Args) -> Option<config::Output> {
+    match (args.json, args.widget) {
+        (true, _) => Some(config::Output::Json),
+        (_, true) => Some(config::Output::Widget),
+        _ => None,
+    }
+}
+
+/// Which parameters to fill in a single invocation.
+#[derive(Debug, PartialEq)]
+enum FillMode {
+    /// Fill or cycle one parameter per invocation
+    One,
+    /// Fill every missing required parameter
+    Required,
+    /// Fill every missing parameter
+    All,
+}
+
 #[derive(Debug, serde::Serialize)]
 struct OutputMetadata {
     cursor_position: usize,
     stdout: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     error: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    candidates: Vec<String>,
+}
+
+/// One of the completions printed with `--candidates`.
+#[derive(Debug, serde::Serialize)]
+struct Candidate {
+    /// What the completion fills in, such as a path, a method or a parameter name
+    label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    cursor_position: usize,
+    stdout: String,
+}
+
+/// Extra information used when printing the rewritten command.
+#[derive(Debug, Default)]
+struct OutputHints {
+    /// Place the cursor here instead of at the end of the command
+    cursor: Option<CommandCursor>,
+    /// Alternatives the user can choose from, such as every path matching a partial URL
+    candidates: Vec<String>,
+}
+
+#[derive(Debug)]
+enum CommandCursor {
+    /// Just after the method
+    Method,
+    /// At an offset inside the URL
+    Url(usize),
+    /// At the end of a header's value
+    Header(String),
+    /// At the end of a `--data-urlencode` field's value
+    Query(String),
+    /// At the end of a cookie's value
+    Cookie(String),
+    /// At the end of a body property's value, or of the body
+    Body(Option<String>),
 }
 
 fn main() -> anyhow::Result<()> {
     // Parse command line arguments
     let args = Args::parse();
-    let json_out = args.json;
-
-    let mut buffer = String::new();
-    io::stdin()
-        .read_to_string(&mut buffer)
-        .expect("Failed to read from stdin");
-    if buffer.is_empty() {
-        std::process::exit(0);
-    }
-
-    let arg_path_prefix = match &args.path_prefix {
-        Some(prefix) => {
-            if !prefix.starts_with('/') {
-                print_error(&buffer, "Path prefix must start with a slash", json_out);
-                std::process::exit(1);
+    let config_paths = config::paths(args.config.as_deref());
+    match &args.command {
+        Some(Command::Init { shell, args }) => {
+            io::stdout().write_all(init::widget(*shell, args).as_bytes())?;
+            return Ok(());
+        }
+        Some(Command::Config {
+            command: ConfigCommand::Show,
+        }) => {
+            let mut config = config::load_all(&config_paths)?;
+            // Flags take precedence over every file
+            if let Some(spec) = &args.spec {
+                config.spec = Some(spec.to_string_lossy().to_string());
             }
-            if prefix.ends_with('/') {
-                print_error(&buffer, "Path prefix must not end with a slash", json_out);
-                std::process::exit(1);
+            config.path_prefix = args.path_prefix.clone().or(config.path_prefix);
+            config.output = flag_output(&args).or(config.output);
+            for path in config_paths.iter() {
+                println!("# {}", path.display());
             }
-            prefix.to_string()
+            io::stdout().write_all(toml::to_string(&config)?.as_bytes())?;
+            return Ok(());
         }
-        None => String::new(),
+        Some(Command::Lint) | None => {}
+    }
+    let is_lint = matches!(args.command, Some(Command::Lint));
+    if args.spec.is_none() && config_paths.is_empty() {
+        // Without a configuration file there is nowhere else to find the specification, so
+        // report it as missing the way clap would
+        Args::command()
+            .mut_arg("spec", |spec| spec.required(true))
+            .get_matches();
+    }
+    // The configuration can't change how its own errors are printed
+    let output_format = OutputFormat::new(&args, None);
+    let fill_mode = if args.all {
+        FillMode::All
+    } else if args.all_required {
+        FillMode::Required
+    } else {
+        FillMode::One
     };
 
-    let mut split_by_pipes = buffer.split('|');
-    let curl_command_position = split_by_pipes.position(|part| part.trim().starts_with("curl"));
-    if curl_command_position.is_none() {
-        print_error(&buffer, "", json_out);
+    let mut buffer = String::new();
+    io::stdin()
+        .read_to_string(&mut buffer)
+        .expect("Failed to read from stdin");
+    // There's nothing to complete, but lint reports the missing command
+    if buffer.is_empty() && !is_lint {
         std::process::exit(0);
     }
-    let curl_command_position = curl_command_position.unwrap();
-    let curl_command = buffer.split('|').nth(curl_command_position).unwrap().trim();
 
-    let spec_path = args.spec;
-    if !spec_path.exists() {
-        io::stdout().write_all(buffer.as_bytes())?;
-        print_error(&buffer, "Specification path does not exist", json_out);
-        std::process::exit(1);
-    }
-    let spec_content = std::fs::read_to_string(&spec_path);
-    if let Err(e) = spec_content {
+    let config = config::load_all(&config_paths);
+    if let Err(e) = config {
         print_error(
             &buffer,
-            &format!("Failed to read specification file: {}", e),
-            json_out,
+            &format!("Failed to read configuration file: {}", e),
+            output_format,
         );
         std::process::exit(1);
     }
-    let spec_content = spec_content.unwrap();
-    if spec_content.is_empty() {
-        print_error(&buffer, "Specification file is empty", json_out);
-        std::process::exit(1);
-    }
-    // For JSON spec
-    let spec: Result<openapiv3::OpenAPI, anyhow::Error> = if spec_path.ends_with(".json") {
-        serde_json::from_str(&spec_content)
-            .map_err(|e| anyhow::anyhow!("Failed to parse JSON OpenAPI spec: {}", e))
-    } else {
-        // For YAML spec
-        serde_yaml::from_str(&spec_content)
-            .map_err(|e| anyhow::anyhow!("Failed to parse YAML OpenAPI spec: {}", e))
-    };
-    if let Err(e) = spec {
-        print_error(
-            &buffer,
-            &format!("Failed to deserialize specification as json or yaml: {}", e),
-            json_out,
-        );
-        std::process::exit(1);
+    let config = config.unwrap();
+    let output_format = OutputFormat::new(&args, config.output);
+
+    // Shells count the cursor position in characters rather than bytes
+    let cursor = args.cursor.map(|cursor| {
+        buffer
+            .char_indices()
+            .nth(cursor)
+            .map_or(buffer.len(), |(offset, _)| offset)
+    });
+    let curl_command_range = shell::find_curl_command(&buffer, cursor);
+    if curl_command_range.is_none() {
+        if is_lint {
+            print_problems_and_exit(vec![lint::no_curl_command()], output_format);
+        }
+        print_error(&buffer, "", output_format);
+        std::process::exit(0);
     }
-    let spec = spec.unwrap();
+    let curl_command_range = curl_command_range.unwrap();
+    let curl_command = shell::join_continuations(&buffer[curl_command_range.clone()]);
+    // A cursor at the end of the command isn't editing anything in particular
+    let focus = cursor
+        .filter(|cursor| curl_command_range.contains(cursor))
+        .and_then(|cursor| focus::focus(&curl_command, cursor - curl_command_range.start));
 
-    let parsed_request = curl_parser::ParsedRequest::from_str(curl_command);
+    // The curl parser doesn't understand -b, -F or -u, so they are tracked separately
+    let (curl_command, mut curl_options) = options::extract(&curl_command);
+    let parsed_request = curl_parser::ParsedRequest::from_str(&curl_command);
     if let Err(e) = parsed_request {
         print_error(
             &buffer,
             &format!("Failed parsing curl command: {}", e),
-            json_out,
+            output_format,
         );
         std::process::exit(1);
     }
     let mut parsed_request = parsed_request.unwrap();
     parsed_request.headers.remove(http::header::ACCEPT);
-    let path = parsed_request.url.path();
+    let host_config = config.host(&parsed_request.url);
+    // Headers the configuration adds, the host's taking precedence
+    let default_headers = host_config
+        .into_iter()
+        .flat_map(|host_config| host_config.headers.iter())
+        .chain(config.headers.iter());
+    // Linting checks the command as it was written
+    for (name, value) in default_headers.filter(|_| !is_lint) {
+        if let Ok(name) = http::header::HeaderName::from_str(name)
+            && let Ok(value) = http::header::HeaderValue::from_str(value)
+            && !parsed_request.headers.contains_key(&name)
+        {
+            parsed_request.headers.insert(name, value);
+        }
+    }
+    let spec_location = args
+        .spec
+        .or_else(|| {
+            host_config.and_then(|host_config| host_config.spec.as_ref().map(PathBuf::from))
+        })
+        .or_else(|| config.spec.as_ref().map(PathBuf::from));
+    let spec_location = match spec_location {
+        Some(spec_location) => spec_location,
+        None => {
+            print_error(
+                &buffer,
+                &format!(
+                    "No specification given or configured for {}",
+                    parsed_request.url
+                ),
+                output_format,
+            );
+            std::process::exit(1);
+        }
+    };
+    // A name from the registry stands for the path or URL it's registered with
+    let spec_location = match config.specs.get(&*spec_location.to_string_lossy()) {
+        Some(registered) if !spec_location.exists() => PathBuf::from(registered),
+        _ => spec_location,
+    };
+    let spec_path = match spec_location.to_str() {
+        Some(url) if remote::is_url(url) => match remote::fetch(url) {
+            Ok(spec_path) => spec_path,
+            Err(e) => {
+                print_error(
+                    &buffer,
+                    &format!("Failed to fetch specification: {}", e),
+                    output_format,
+                );
+                std::process::exit(1);
+            }
+        },
+        _ => spec_location,
+    };
+    if !spec_path.exists() {
+        print_error(&buffer, "Specification path does not exist", output_format);
+        std::process::exit(1);
+    }
+    // A cached specification has no path items until one is matched, only their outlines
+    let (mut spec, outlines, deferred) = match cache::load(&spec_path) {
+        Some((spec, outlines, deferred)) => (spec, outlines, Some(deferred)),
+        None => {
+            let (spec, sources) = read_spec(&spec_path, &buffer, output_format);
+            // Without a cache the next invocation only takes longer
+            cache::store(&spec_path, &sources, &spec).unwrap_or_default();
+            let outlines = outline::outlines(&spec);
+            (spec, outlines, None)
+        }
+    };
+
+    let path_prefix = args
+        .path_prefix
+        .as_ref()
+        .or(host_config.and_then(|host_config| host_config.path_prefix.as_ref()))
+        .or(config.path_prefix.as_ref());
+    let arg_path_prefix = match path_prefix {
+        Some(prefix) => {
+            if !prefix.starts_with('/') {
+                print_error(
+                    &buffer,
+                    "Path prefix must start with a slash",
+                    output_format,
+                );
+                std::process::exit(1);
+            }
+            if prefix.ends_with('/') {
+                print_error(
+                    &buffer,
+                    "Path prefix must not end with a slash",
+                    output_format,
+                );
+                std::process::exit(1);
+            }
+            prefix.to_string()
+        }
+        None => String::new(),
+    };
+
+    if parsed_request.url.authority().is_none() {
+        // Only a path was given, so complete the scheme and host from the specification
+        let servers = server::applicable_servers(
+            &spec,
+            &outlines,
+            parsed_request.url.path(),
+            parsed_request.method.as_str(),
+        );
+        let path_and_query = parsed_request
+            .url
+            .path_and_query()
+            .map(|path_and_query| path_and_query.as_str())
+            .unwrap_or("/")
+            .to_string();
+        if output_format.is_candidates() && args.server.is_none() && servers.len() > 1 {
+            let mut candidates = Vec::new();
+            for (index, candidate) in servers.iter().enumerate() {
+                let url = server::url_on_server(candidate, &path_and_query);
+                if let Ok(url) = url.parse() {
+                    parsed_request.url = url;
+                    candidates.push(candidate_for(
+                        index.to_string(),
+                        candidate.description.clone(),
+                        &parsed_request,
+                        &curl_options,
+                        &buffer,
+                        curl_command_range.clone(),
+                        None,
+                    ));
+                }
+            }
+            print_candidates_and_exit(candidates, output_format);
+        }
+        if let Some(selected_server) = server::select(servers, args.server.as_deref()) {
+            let url = server::url_on_server(selected_server, &path_and_query);
+            match url.parse() {
+                Ok(url) => parsed_request.url = url,
+                Err(e) => {
+                    print_error(
+                        &buffer,
+                        &format!("Failed to complete server: {}", e),
+                        output_format,
+                    );
+                    std::process::exit(1);
+                }
+            }
+        } else if let Some(selection) = &args.server {
+            print_error(
+                &buffer,
+                &format!("No server in specification matches {}", selection),
+                output_format,
+            );
+            std::process::exit(1);
+        }
+    }
+    let arg_path_prefix = match path_prefix {
+        Some(_) => arg_path_prefix,
+        None => server::matching_base_path(&spec, &outlines, parsed_request.url.path()),
+    };
+    let original_path = parsed_request.url.path().to_string();
+    let mut path = original_path.clone();
     let mut wayfinder = wayfind::Router::new();
-    for (path_template, _) in spec.paths.paths.iter() {
+    for path_template in outlines.keys() {
         let path_template = format!("{}{}", &arg_path_prefix, path_template);
         wayfinder.insert(&path_template, ()).unwrap();
     }
-    let wayfinder_match = wayfinder.search(path);
+    if wayfinder.search(&path).is_none() && path.ends_with('/') {
+        // A trailing slash is treated as an empty placeholder for the next path parameter
+        path.push_str("{}");
+    }
+    let wayfinder_match = wayfinder.search(&path);
+    if let (Some(deferred), Some(wayfinder_match)) = (&deferred, &wayfinder_match) {
+        let template = wayfinder_match
+            .template
+            .trim_start_matches(&arg_path_prefix);
+        deferred.load(&mut spec, template);
+    }
+    if is_lint {
+        let template = wayfinder_match.as_ref().map(|wayfinder_match| {
+            wayfinder_match
+                .template
+                .trim_start_matches(&arg_path_prefix)
+        });
+        let problems = lint::lint(
+            &spec,
+            &config,
+            template,
+            path.strip_prefix(&arg_path_prefix).unwrap_or(&path),
+            &parsed_request,
+            &curl_options,
+        );
+        print_problems_and_exit(problems, output_format);
+    }
     if wayfinder_match.is_none() {
-        print_error(&buffer, "No matching path in specification", json_out);
-        std::process::exit(1);
+        let templates = outlines
+            .keys()
+            .map(|template| format!("{}{}", &arg_path_prefix, template))
+            .collect::<Vec<_>>();
+        let candidates = path_completion::complete_path(&original_path, &templates);
+        if candidates.is_empty() {
+            print_error(&buffer, "No matching path in specification", output_format);
+            std::process::exit(1);
+        }
+        let url = parsed_request.url.to_string();
+        let path_start = url_path_start(&url);
+        if output_format.is_candidates() {
+            let mut path_candidates = Vec::new();
+            for candidate in candidates.iter() {
+                let candidate_url = format!(
+                    "{}{}{}",
+                    &url[..path_start],
+                    candidate,
+                    &url[path_start + original_path.len()..]
+                );
+                if let Ok(candidate_url) = candidate_url.parse() {
+                    parsed_request.url = candidate_url;
+                    path_candidates.push(candidate_for(
+                        candidate.to_string(),
+                        outlines
+                            .get(candidate.trim_start_matches(&arg_path_prefix))
+                            .and_then(|outline| outline.summary.clone()),
+                        &parsed_request,
+                        &curl_options,
+                        &buffer,
+                        curl_command_range.clone(),
+                        Some(CommandCursor::Url(path_start + candidate.len())),
+                    ));
+                }
+            }
+            print_candidates_and_exit(path_candidates, output_format);
+        }
+        let completed_path = match candidates.as_slice() {
+            [candidate] => candidate.to_string(),
+            _ => path_completion::common_prefix(&candidates),
+        };
+        let completed_path = if completed_path.len() > original_path.len() {
+            completed_path
+        } else {
+            original_path.clone()
+        };
+        let url = format!(
+            "{}{}{}",
+            &url[..path_start],
+            completed_path,
+            &url[path_start + original_path.len()..]
+        );
+        match url.parse() {
+            Ok(url) => parsed_request.url = url,
+            Err(e) => {
+                print_error(
+                    &buffer,
+                    &format!("Failed to complete path: {}", e),
+                    output_format,
+                );
+                std::process::exit(1);
+            }
+        }
+        let candidates = match candidates.len() {
+            1 => vec![],
+            _ => candidates,
+        };
+        print_result_and_exit(
+            &parsed_request,
+            output_format,
+            &curl_options,
+            &buffer,
+            curl_command_range.clone(),
+            OutputHints {
+                cursor: Some(CommandCursor::Url(path_start + completed_path.len())),
+                candidates,
+            },
+        );
     }
     let wayfinder_match = wayfinder_match.unwrap();
     let template = wayfinder_match
@@ -147,15 +629,91 @@ fn main() -> anyhow::Result<()> {
 
     let match_path = spec.paths.paths.get(template);
     if match_path.is_none() {
-        print_error(&buffer, "No matching path in specification", json_out);
+        print_error(&buffer, "No matching path in specification", output_format);
         std::process::exit(1);
     }
     let match_path = match_path.unwrap().as_item();
     if match_path.is_none() {
-        print_error(&buffer, "No matching path in specification", json_out);
+        print_error(&buffer, "No matching path in specification", output_format);
         std::process::exit(1);
     }
     let match_path = match_path.unwrap();
+    let available_methods = match_path
+        .iter()
+        .map(|(method, _)| method.to_uppercase())
+        .collect::<Vec<_>>();
+    if available_methods.is_empty() {
+        print_error(
+            &buffer,
+            "No operations defined for path in specification",
+            output_format,
+        );
+        std::process::exit(1);
+    }
+    let is_method_defined = available_methods.contains(&parsed_request.method.to_string());
+    if output_format.is_candidates()
+        && available_methods.len() > 1
+        && (focus == Some(focus::Focus::Method) || !is_method_defined)
+    {
+        let mut candidates = Vec::new();
+        for method in available_methods.iter() {
+            parsed_request.method = http::Method::from_str(method).unwrap();
+            let summary = match_path
+                .iter()
+                .find(|(operation_method, _)| operation_method.eq_ignore_ascii_case(method))
+                .and_then(|(_, operation)| operation.summary.clone());
+            candidates.push(candidate_for(
+                method.to_string(),
+                summary,
+                &parsed_request,
+                &curl_options,
+                &buffer,
+                curl_command_range.clone(),
+                Some(CommandCursor::Method),
+            ));
+        }
+        print_candidates_and_exit(candidates, output_format);
+    }
+    if focus == Some(focus::Focus::Method) {
+        // Move on to the next method the path defines
+        let position = available_methods
+            .iter()
+            .position(|method| *method == parsed_request.method.as_str());
+        let next = match position {
+            Some(position) => &available_methods[(position + 1) % available_methods.len()],
+            None => &available_methods[0],
+        };
+        parsed_request.method = http::Method::from_str(next).unwrap();
+        print_result_and_exit(
+            &parsed_request,
+            output_format,
+            &curl_options,
+            &buffer,
+            curl_command_range.clone(),
+            OutputHints {
+                cursor: Some(CommandCursor::Method),
+                candidates: available_methods.clone(),
+            },
+        );
+    }
+    if !available_methods.contains(&parsed_request.method.to_string()) {
+        // Either there was no -X and curl's default isn't defined, or the method is wrong. Pick
+        // the first method the path defines, and let the user choose when there are several.
+        parsed_request.method = http::Method::from_str(&available_methods[0]).unwrap();
+        if available_methods.len() > 1 {
+            print_result_and_exit(
+                &parsed_request,
+                output_format,
+                &curl_options,
+                &buffer,
+                curl_command_range.clone(),
+                OutputHints {
+                    cursor: Some(CommandCursor::Method),
+                    candidates: available_methods,
+                },
+            );
+        }
+    }
     let method = parsed_request.method.as_str();
     let operation = match method {
         "GET" => &match_path.get,
@@ -165,24 +723,283 @@ fn main() -> anyhow::Result<()> {
         "PATCH" => &match_path.patch,
         "HEAD" => &match_path.head,
         "OPTIONS" => &match_path.options,
+        "TRACE" => &match_path.trace,
         _ => &None,
     };
     if operation.is_none() {
-        print_error(&buffer, "No matching operation in specification", json_out);
+        print_error(
+            &buffer,
+            "No matching operation in specification",
+            output_format,
+        );
         std::process::exit(1);
     }
     let operation = operation.as_ref().unwrap();
-    let parameters = parameter_map(&operation.parameters, &spec.components);
+    let parameters = parameter_map(
+        &match_path.parameters,
+        &operation.parameters,
+        &spec.components,
+    );
     if let Err(e) = parameters {
         print_error(
             &buffer,
             &format!("Failed to retrieve parameters: {}", e),
-            json_out,
+            output_format,
         );
         std::process::exit(1);
     }
     let parameters = parameters.unwrap();
-    let first_empty_spec_parameter = get_first_empty_spec_parameter(&parameters, &parsed_request);
+    let credentials = security::credentials(operation, &spec, &config);
+    if let Err(e) = credentials {
+        print_error(
+            &buffer,
+            &format!("Failed to retrieve security requirements: {}", e),
+            output_format,
+        );
+        std::process::exit(1);
+    }
+    let credentials = credentials.unwrap();
+
+    let request_body = match operation.request_body.as_ref() {
+        Some(request_body) => match request_body.item(&spec.components) {
+            Ok(request_body) => Some(request_body),
+            Err(e) => {
+                print_error(
+                    &buffer,
+                    &format!("Error retrieving request body: {}", e),
+                    output_format,
+                );
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+    let content_type = parsed_request
+        .headers
+        .get(http::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string());
+    let selected_media_type = request_body.and_then(|request_body| {
+        body::select_media_type(&request_body.content, content_type.as_deref())
+    });
+    if let Some((_, _, body::BodyKind::UrlEncoded)) = selected_media_type {
+        // Without -G, --data-urlencode fields that aren't query parameters are the form body
+        let query_names = parameters
+            .values()
+            .filter(|parameter| matches!(parameter, Parameter::Query { .. }))
+            .map(|parameter| parameter.parameter_data_ref().name.to_string())
+            .chain(
+                credentials
+                    .iter()
+                    .filter_map(|credential| match credential {
+                        security::Credential::Query { name, .. } => Some(name.to_string()),
+                        _ => None,
+                    }),
+            )
+            .collect::<Vec<_>>();
+        let field_names = parsed_request
+            .data_url_encoded
+            .keys()
+            .filter(|name| !query_names.contains(name))
+            .cloned()
+            .collect::<Vec<_>>();
+        let mut fields = IndexMap::new();
+        for name in field_names {
+            if let Some(value) = parsed_request.data_url_encoded.remove(&name) {
+                fields.insert(name, value);
+            }
+        }
+        if !fields.is_empty() {
+            curl_options.form = form::Form::UrlEncoded(fields);
+        }
+    }
+    // With a body, query parameters are written into the URL, so the ones already there are
+    // filled in like --data-urlencode ones and written back with them
+    let has_body = parsed_request.body().is_some() || !curl_options.form.is_empty();
+    if has_body && let Some(query) = parsed_request.url.query() {
+        let query = form_urlencoded::parse(query.as_bytes())
+            .into_owned()
+            .collect::<Vec<_>>();
+        let mut parts = parsed_request.url.clone().into_parts();
+        parts.path_and_query = http::uri::PathAndQuery::from_str(parsed_request.url.path()).ok();
+        if let Ok(url) = http::Uri::from_parts(parts) {
+            parsed_request.url = url;
+            parsed_request.data_url_encoded.extend(query);
+        }
+    }
+    curl_options.variables = config.variables();
+    for credential in credentials {
+        insert_credential(credential, &mut parsed_request, &mut curl_options);
+    }
+
+    let path_parameters = items(&match_path.parameters, &spec.components)
+        .chain(items(&operation.parameters, &spec.components))
+        .filter_map(|parameter| parameter.ok())
+        .filter(|parameter| matches!(parameter, Parameter::Path { .. }))
+        .collect::<Vec<_>>();
+    if let Some((substituted_path, path_cursor)) = substitute_path_parameters(
+        &path,
+        wayfinder_match.template,
+        &path_parameters,
+        &spec.components,
+    ) {
+        let url = parsed_request.url.to_string();
+        let path_start = url_path_start(&url);
+        let url = format!(
+            "{}{}{}",
+            &url[..path_start],
+            substituted_path,
+            &url[path_start + original_path.len()..]
+        );
+        match url.parse() {
+            Ok(url) => parsed_request.url = url,
+            Err(e) => {
+                print_error(
+                    &buffer,
+                    &format!("Failed to substitute path parameters: {}", e),
+                    output_format,
+                );
+                std::process::exit(1);
+            }
+        }
+        if fill_mode == FillMode::One || focus == Some(focus::Focus::Url) {
+            print_result_and_exit(
+                &parsed_request,
+                output_format,
+                &curl_options,
+                &buffer,
+                curl_command_range.clone(),
+                OutputHints {
+                    cursor: Some(CommandCursor::Url(path_start + path_cursor)),
+                    ..Default::default()
+                },
+            );
+        }
+    }
+    if focus == Some(focus::Focus::Url) {
+        // Nothing in the URL is left to complete
+        let url = parsed_request.url.to_string();
+        let path_end = url_path_start(&url) + parsed_request.url.path().len();
+        print_result_and_exit(
+            &parsed_request,
+            output_format,
+            &curl_options,
+            &buffer,
+            curl_command_range.clone(),
+            OutputHints {
+                cursor: Some(CommandCursor::Url(path_end)),
+                ..Default::default()
+            },
+        );
+    }
+    if let Some(focus::Focus::Body(property)) = &focus {
+        if let Some((_, media_type, body::BodyKind::Json)) = selected_media_type
+            && let Some(schema) = &media_type.schema
+            && let Some(body) = parsed_request.body()
+            && let Ok(mut value) = serde_json::from_str::<serde_json::Value>(&body.to_string())
+            && let serde_json::Value::Object(object) = &mut value
+        {
+            let examples = match example::property_examples(schema, &spec.components) {
+                Ok(examples) => examples,
+                Err(e) => {
+                    print_error(
+                        &buffer,
+                        &format!("Error generating request body: {}", e),
+                        output_format,
+                    );
+                    std::process::exit(1);
+                }
+            };
+            for (name, example) in examples {
+                match property {
+                    // The property under the cursor is reset to its example
+                    Some(property) if *property == name => {
+                        object.insert(name, example);
+                    }
+                    Some(_) => {}
+                    // Between properties, the missing ones are added
+                    None => {
+                        object.entry(name).or_insert(example);
+                    }
+                }
+            }
+            parsed_request.body = vec![value.to_string()];
+        }
+        print_result_and_exit(
+            &parsed_request,
+            output_format,
+            &curl_options,
+            &buffer,
+            curl_command_range.clone(),
+            OutputHints {
+                cursor: Some(CommandCursor::Body(property.clone())),
+                ..Default::default()
+            },
+        );
+    }
+
+    if output_format.is_candidates() && fill_mode == FillMode::One {
+        let candidates = parameter_candidates(
+            &focus,
+            &parameters,
+            &mut parsed_request,
+            &mut curl_options,
+            &spec.components,
+            &buffer,
+            curl_command_range.clone(),
+        );
+        if !candidates.is_empty() {
+            print_candidates_and_exit(candidates, output_format);
+        }
+    }
+
+    let first_empty_spec_parameter = match &focus {
+        Some(
+            focus @ (focus::Focus::Header(_) | focus::Focus::Query(_) | focus::Focus::Cookie(_)),
+        ) => {
+            let focused = focused_spec_parameter(
+                focus,
+                &parameters,
+                &parsed_request,
+                &curl_options.cookies,
+                &spec.components,
+            );
+            if focused.is_none() {
+                // A parameter with a value moves on to its next enum value, other parameters
+                // with a value, or that the specification doesn't define, are kept
+                if let Some(parameter) = focused_parameter(focus, &parameters)
+                    && let Some(current) =
+                        parameter_value(parameter, &parsed_request, &curl_options.cookies)
+                    && let Some(next) = next_enum_value(parameter, &current, &spec.components)
+                {
+                    set_parameter(
+                        parameter,
+                        Some(&next),
+                        &mut parsed_request,
+                        &mut curl_options.cookies,
+                    );
+                }
+                print_result_and_exit(
+                    &parsed_request,
+                    output_format,
+                    &curl_options,
+                    &buffer,
+                    curl_command_range.clone(),
+                    OutputHints {
+                        cursor: focus_cursor(focus),
+                        ..Default::default()
+                    },
+                );
+            }
+            focused
+        }
+        _ => get_first_empty_spec_parameter(
+            &parameters,
+            &parsed_request,
+            &curl_options.cookies,
+            &spec.components,
+        ),
+    };
     let populated_header_names = parsed_request
         .headers
         .iter()
@@ -205,210 +1022,538 @@ fn main() -> anyhow::Result<()> {
             }
         })
         .collect::<Vec<_>>();
+    let populated_cookie_names = curl_options
+        .cookies
+        .values
+        .iter()
+        .filter_map(|(name, value)| {
+            if value.is_empty() {
+                None
+            } else {
+                Some(name.to_string())
+            }
+        })
+        .collect::<Vec<_>>();
 
-    match first_empty_spec_parameter {
-        Some(empty_parameter) => {
-            let replacement_paremeter = match empty_parameter {
-                EmptySpecParameter::Header(name) => {
-                    parsed_request.headers.remove(&name);
-                    let parameter_position =
-                        parameters.iter().position(|(n, _)| *n == &name).unwrap();
-                    let mut next_parameter_iter = parameters.iter().cycle();
-                    let mut next_parameter =
-                        next_parameter_iter.nth(parameter_position + 1).unwrap();
-                    let max_iterations = parameters.len();
-                    let mut iterations = 0;
-                    loop {
-                        let next_name = next_parameter.1.parameter_data_ref().name.to_string();
-                        if !populated_header_names.contains(&next_name)
-                            || iterations >= max_iterations
-                        {
-                            break;
+    if fill_mode != FillMode::One {
+        for (_, parameter) in parameters.iter() {
+            let required = parameter.parameter_data_ref().required;
+            if (fill_mode == FillMode::Required && !required)
+                || is_parameter_set(parameter, &parsed_request, &curl_options.cookies)
+            {
+                continue;
+            }
+            insert_parameter(
+                parameter,
+                &mut parsed_request,
+                &mut curl_options.cookies,
+                &spec.components,
+            );
+        }
+    } else {
+        match first_empty_spec_parameter {
+            Some(empty_parameter) => {
+                let replacement_paremeter = match empty_parameter {
+                    EmptySpecParameter::Header(name) => {
+                        parsed_request.headers.remove(&name);
+                        let parameter_position =
+                            parameters.iter().position(|(n, _)| *n == &name).unwrap();
+                        let mut next_parameter_iter = parameters.iter().cycle();
+                        let mut next_parameter =
+                            next_parameter_iter.nth(parameter_position + 1).unwrap();
+                        let max_iterations = parameters.len();
+                        let mut iterations = 0;
+                        loop {
+                            let next_name = next_parameter.1.parameter_data_ref().name.to_string();
+                            // The swapped parameter's own value isn't the user's
+                            if !populated_header_names.contains(&next_name)
+                                || next_name == name
+                                || iterations >= max_iterations
+                            {
+                                break;
+                            }
+                            next_parameter = next_parameter_iter.next().unwrap();
+                            iterations += 1;
                         }
-                        next_parameter = next_parameter_iter.next().unwrap();
-                        iterations += 1;
-                    }
 
-                    next_parameter.1
-                }
-                EmptySpecParameter::Query(name) => {
-                    parsed_request.data_url_encoded.remove(&name);
-                    let parameter_position =
-                        parameters.iter().position(|(n, _)| *n == &name).unwrap();
-                    let mut next_parameter_iter = parameters.iter().cycle();
-                    let mut next_parameter =
-                        next_parameter_iter.nth(parameter_position + 1).unwrap();
-                    let max_iterations = parameters.len();
-                    let mut iterations = 0;
-                    loop {
-                        let next_name = next_parameter.1.parameter_data_ref().name.to_string();
-                        if !populated_query_names.contains(&next_name)
-                            || iterations >= max_iterations
-                        {
-                            break;
+                        next_parameter.1
+                    }
+                    EmptySpecParameter::Query(name) => {
+                        parsed_request.data_url_encoded.remove(&name);
+                        let parameter_position =
+                            parameters.iter().position(|(n, _)| *n == &name).unwrap();
+                        let mut next_parameter_iter = parameters.iter().cycle();
+                        let mut next_parameter =
+                            next_parameter_iter.nth(parameter_position + 1).unwrap();
+                        let max_iterations = parameters.len();
+                        let mut iterations = 0;
+                        loop {
+                            let next_name = next_parameter.1.parameter_data_ref().name.to_string();
+                            // The swapped parameter's own value isn't the user's
+                            if !populated_query_names.contains(&next_name)
+                                || next_name == name
+                                || iterations >= max_iterations
+                            {
+                                break;
+                            }
+                            next_parameter = next_parameter_iter.next().unwrap();
+                            iterations += 1;
                         }
-                        next_parameter = next_parameter_iter.next().unwrap();
-                        iterations += 1;
+
+                        next_parameter.1
                     }
+                    EmptySpecParameter::Cookie(name) => {
+                        curl_options.cookies.values.shift_remove(&name);
+                        let parameter_position =
+                            parameters.iter().position(|(n, _)| *n == &name).unwrap();
+                        let mut next_parameter_iter = parameters.iter().cycle();
+                        let mut next_parameter =
+                            next_parameter_iter.nth(parameter_position + 1).unwrap();
+                        let max_iterations = parameters.len();
+                        let mut iterations = 0;
+                        loop {
+                            let next_name = next_parameter.1.parameter_data_ref().name.to_string();
+                            // The swapped parameter's own value isn't the user's
+                            if !populated_cookie_names.contains(&next_name)
+                                || next_name == name
+                                || iterations >= max_iterations
+                            {
+                                break;
+                            }
+                            next_parameter = next_parameter_iter.next().unwrap();
+                            iterations += 1;
+                        }
 
-                    next_parameter.1
-                }
-            };
-            match replacement_paremeter {
-                Parameter::Header { parameter_data, .. } => {
-                    let name = &parameter_data.name;
-                    let value = parameter_data
-                        .example
-                        .as_ref()
-                        .unwrap_or(&serde_json::Value::String("".to_string()))
-                        .to_string();
-                    let header_value = http::header::HeaderValue::from_str(&value)
-                        .unwrap_or_else(|_| http::header::HeaderValue::from_static("invalid"));
-                    parsed_request.headers.insert(
-                        http::header::HeaderName::from_str(name).unwrap(),
-                        header_value,
+                        next_parameter.1
+                    }
+                };
+                insert_parameter(
+                    replacement_paremeter,
+                    &mut parsed_request,
+                    &mut curl_options.cookies,
+                    &spec.components,
+                );
+                if focus.is_some() {
+                    let cursor = parameter_cursor(replacement_paremeter);
+                    print_result_and_exit(
+                        &parsed_request,
+                        output_format,
+                        &curl_options,
+                        &buffer,
+                        curl_command_range.clone(),
+                        OutputHints {
+                            cursor,
+                            ..Default::default()
+                        },
                     );
                 }
-                Parameter::Query { parameter_data, .. } => {
-                    let name = &parameter_data.name;
-                    let value = parameter_data.example.as_ref();
-                    let value = match value {
-                        Some(v) => v.to_string(),
-                        None => "".to_string(),
-                    };
-                    parsed_request
-                        .data_url_encoded
-                        .insert(name.to_string(), value);
-                }
-                _ => {}
             }
-        }
-        None => {
-            for (_, parameter) in parameters.iter() {
-                if let Parameter::Header { parameter_data, .. } = parameter {
-                    let name = &parameter_data.name;
-                    if parsed_request.headers.contains_key(name) {
-                        // If the header is already set, skip it
-                        continue;
-                    }
-                    let value = parameter_data
-                        .example
-                        .as_ref()
-                        .unwrap_or(&serde_json::Value::String("".to_string()))
-                        .to_string();
-                    let header_value = http::header::HeaderValue::from_str(&value)
-                        .unwrap_or_else(|_| http::header::HeaderValue::from_static("invalid"));
-                    parsed_request.headers.insert(
-                        http::header::HeaderName::from_str(name).unwrap(),
-                        header_value,
-                    );
-                    break;
-                } else if let Parameter::Query { parameter_data, .. } = parameter {
-                    let name = &parameter_data.name;
-                    if parsed_request.data_url_encoded.contains_key(name) {
-                        // If the query parameter is already set, skip it
-                        continue;
+            None => {
+                let unset_parameter = parameters.values().find(|parameter| {
+                    !is_parameter_set(parameter, &parsed_request, &curl_options.cookies)
+                });
+                match unset_parameter {
+                    Some(parameter) => insert_parameter(
+                        parameter,
+                        &mut parsed_request,
+                        &mut curl_options.cookies,
+                        &spec.components,
+                    ),
+                    // Every parameter is in the command, so the last one with an enum moves on
+                    // to its next value
+                    None => {
+                        let next = parameters.values().rev().find_map(|parameter| {
+                            let current =
+                                parameter_value(parameter, &parsed_request, &curl_options.cookies)?;
+                            let next = next_enum_value(parameter, &current, &spec.components)?;
+                            Some((*parameter, next))
+                        });
+                        if let Some((parameter, next)) = next {
+                            set_parameter(
+                                parameter,
+                                Some(&next),
+                                &mut parsed_request,
+                                &mut curl_options.cookies,
+                            );
+                        }
                     }
-                    let value = parameter_data.example.as_ref();
-                    let value = match value {
-                        Some(v) => v.to_string(),
-                        None => "".to_string(),
-                    };
-                    parsed_request
-                        .data_url_encoded
-                        .insert(name.to_string(), value);
-                    break;
                 }
             }
-        }
-    };
+        };
+    }
 
     // Bail early if the request body is already set
-    if parsed_request.body().is_some() {
-        print_result_and_exit(&parsed_request, json_out, &buffer, curl_command_position);
+    if parsed_request.body().is_some() || !curl_options.form.is_empty() {
+        print_result_and_exit(
+            &parsed_request,
+            output_format,
+            &curl_options,
+            &buffer,
+            curl_command_range.clone(),
+            OutputHints::default(),
+        );
     }
 
-    let body = operation.request_body.as_ref();
-    if body.is_none() {
-        // If no request body is defined, just print the request and exit
-        print_result_and_exit(&parsed_request, json_out, &buffer, curl_command_position);
-    }
-    let body = body.as_ref().unwrap();
-    let body = body.item(&spec.components);
-    if body.is_err() {
-        print_error(
+    if selected_media_type.is_none() {
+        // If no request body is defined in a supported media type, just print the request and exit
+        print_result_and_exit(
+            &parsed_request,
+            output_format,
+            &curl_options,
             &buffer,
-            &format!("Error retrieving request body: {}", body.unwrap_err()),
-            json_out,
+            curl_command_range.clone(),
+            OutputHints::default(),
         );
-        std::process::exit(1);
     }
-    let body = body.unwrap();
-    if body.content.get("application/json").is_none() {
-        // If no JSON content is defined, just print the request and exit
-        print_result_and_exit(&parsed_request, json_out, &buffer, curl_command_position);
+    let (media_type_name, media_type, body_kind) = selected_media_type.unwrap();
+    let content_type_kind = content_type
+        .as_deref()
+        .and_then(body::BodyKind::from_media_type);
+    if let Ok(header_value) = http::header::HeaderValue::from_str(media_type_name) {
+        // Keep the user's own Content-Type, and let curl add the multipart boundary itself
+        if content_type_kind != Some(body_kind) && body_kind != body::BodyKind::Multipart {
+            parsed_request
+                .headers
+                .insert(http::header::CONTENT_TYPE, header_value);
+        }
     }
-    let media_type = body.content.get("application/json").unwrap();
-    parsed_request.headers.insert(
-        http::header::CONTENT_TYPE,
-        http::header::HeaderValue::from_static("application/json"),
-    );
-    parsed_request.headers.insert(
-        http::header::ACCEPT,
-        http::header::HeaderValue::from_static("application/json"),
-    );
-    let example = media_type.example.as_ref();
-    if example.is_some() {
-        let example = example.as_ref().unwrap();
-        let example_str = serde_json::to_string(example).unwrap_or_else(|_| "{}".to_string());
-        parsed_request.body = vec![example_str];
-        print_result_and_exit(&parsed_request, json_out, &buffer, curl_command_position);
-        return Ok(());
-    }
-
-    let schema = media_type.schema.as_ref();
-    if schema.is_none() {
-        // If no schema is defined, just print the request and exit
-        print_result_and_exit(&parsed_request, json_out, &buffer, curl_command_position);
-    }
-    let schema = schema.as_ref().unwrap();
-    let schema = schema.item(&spec.components);
-    if schema.is_err() {
-        print_error(
-            &buffer,
-            &format!("Error retrieving example schema: {}", schema.unwrap_err()),
-            json_out,
+    if body_kind == body::BodyKind::Json {
+        parsed_request.headers.insert(
+            http::header::ACCEPT,
+            http::header::HeaderValue::from_static("application/json"),
         );
-        std::process::exit(1);
     }
-    let schema = schema.unwrap();
-    if let openapiv3::SchemaKind::Type(_) = &schema.schema_kind {
-        if schema.schema_data.example.is_some() {
-            // If the schema has an example, use it as the request body
-            let example = schema.schema_data.example.as_ref().unwrap();
-            let example_str = serde_json::to_string(example).unwrap_or_else(|_| "{}".to_string());
-            parsed_request.body = vec![example_str]
+
+    let example = match (&media_type.example, &media_type.schema) {
+        (Some(example), _) => example.clone(),
+        (None, Some(schema)) => match example::example_for_schema(schema, &spec.components) {
+            Ok(example) => example,
+            Err(e) => {
+                print_error(
+                    &buffer,
+                    &format!("Error generating request body: {}", e),
+                    output_format,
+                );
+                std::process::exit(1);
+            }
+        },
+        (None, None) => {
+            // If no example or schema is defined, just print the request and exit
+            print_result_and_exit(
+                &parsed_request,
+                output_format,
+                &curl_options,
+                &buffer,
+                curl_command_range.clone(),
+                OutputHints::default(),
+            );
+            return Ok(());
         }
     };
-    print_result_and_exit(&parsed_request, json_out, &buffer, curl_command_position);
+    match body_kind {
+        body::BodyKind::Json => {
+            let example_str = serde_json::to_string(&example).unwrap_or_else(|_| "{}".to_string());
+            parsed_request.body = vec![example_str];
+        }
+        body::BodyKind::UrlEncoded => {
+            curl_options.form = form::Form::UrlEncoded(body::form_fields(&example));
+        }
+        body::BodyKind::Multipart => {
+            let mut fields = body::form_fields(&example);
+            let binary_properties = media_type
+                .schema
+                .as_ref()
+                .and_then(|schema| example::binary_properties(schema, &spec.components).ok())
+                .unwrap_or_default();
+            for name in binary_properties {
+                // Files are uploaded from a path the user fills in
+                fields.insert(name.to_string(), format!("@path/to/{}", name));
+            }
+            curl_options.form = form::Form::Multipart(fields);
+        }
+        body::BodyKind::Text => {
+            parsed_request.body = vec![example::value_to_string(&example)];
+        }
+        body::BodyKind::Xml => {
+            let xml = match &example {
+                // A string example is already a document
+                serde_json::Value::String(xml) => xml.to_string(),
+                example => {
+                    let root = media_type
+                        .schema
+                        .as_ref()
+                        .and_then(|schema| match schema {
+                            ReferenceOr::Reference { reference } => reference.rsplit('/').next(),
+                            ReferenceOr::Item(_) => None,
+                        })
+                        .unwrap_or("body");
+                    body::to_xml(root, example)
+                }
+            };
+            parsed_request.body = vec![xml];
+        }
+    }
+    print_result_and_exit(
+        &parsed_request,
+        output_format,
+        &curl_options,
+        &buffer,
+        curl_command_range,
+        OutputHints::default(),
+    );
 
     Ok(())
 }
 
+/// Whether the request already has a value (even an empty one) for the parameter.
+///
+/// Path parameters are always part of the URL, so they count as set.
+fn is_parameter_set(
+    parameter: &Parameter,
+    parsed_request: &curl_parser::ParsedRequest,
+    cookies: &cookie::Cookies,
+) -> bool {
+    let name = &parameter.parameter_data_ref().name;
+    match parameter {
+        Parameter::Header { .. } => parsed_request.headers.contains_key(name),
+        Parameter::Query { .. } => parsed_request.data_url_encoded.contains_key(name),
+        Parameter::Cookie { .. } => cookies.values.contains_key(name),
+        Parameter::Path { .. } => true,
+    }
+}
+
+/// Add a parameter to the request using the value the specification suggests for it.
+fn insert_parameter(
+    parameter: &Parameter,
+    parsed_request: &mut curl_parser::ParsedRequest,
+    cookies: &mut cookie::Cookies,
+    components: &Option<Components>,
+) {
+    let value = example::parameter_value(parameter.parameter_data_ref(), components)
+        .map(|value| example::value_to_string(&value))
+        .unwrap_or_default();
+    set_parameter(parameter, Some(&value), parsed_request, cookies);
+}
+
+/// Set a parameter to `value`, or remove it when there's no value.
+fn set_parameter(
+    parameter: &Parameter,
+    value: Option<&str>,
+    parsed_request: &mut curl_parser::ParsedRequest,
+    cookies: &mut cookie::Cookies,
+) {
+    let name = &parameter.parameter_data_ref().name;
+    match (parameter, value) {
+        (Parameter::Header { .. }, Some(value)) => {
+            let header_value = http::header::HeaderValue::from_str(value)
+                .unwrap_or_else(|_| http::header::HeaderValue::from_static("invalid"));
+            parsed_request.headers.insert(
+                http::header::HeaderName::from_str(name).unwrap(),
+                header_value,
+            );
+        }
+        (Parameter::Header { .. }, None) => {
+            parsed_request.headers.remove(name);
+        }
+        (Parameter::Query { .. }, Some(value)) => {
+            parsed_request
+                .data_url_encoded
+                .insert(name.to_string(), value.to_string());
+        }
+        (Parameter::Query { .. }, None) => {
+            parsed_request.data_url_encoded.remove(name);
+        }
+        (Parameter::Cookie { .. }, Some(value)) => {
+            cookies.values.insert(name.to_string(), value.to_string());
+        }
+        (Parameter::Cookie { .. }, None) => {
+            cookies.values.shift_remove(name);
+        }
+        (Parameter::Path { .. }, _) => {}
+    }
+}
+
+/// The value the request has for a parameter, if it has one.
+fn parameter_value(
+    parameter: &Parameter,
+    parsed_request: &curl_parser::ParsedRequest,
+    cookies: &cookie::Cookies,
+) -> Option<String> {
+    let name = &parameter.parameter_data_ref().name;
+    match parameter {
+        Parameter::Header { .. } => parsed_request
+            .headers
+            .get(name)
+            .map(|value| value.to_str().unwrap_or_default().to_string()),
+        Parameter::Query { .. } => parsed_request.data_url_encoded.get(name).cloned(),
+        Parameter::Cookie { .. } => cookies.values.get(name).cloned(),
+        Parameter::Path { .. } => None,
+    }
+}
+
+/// Where the cursor goes to be on a parameter's value.
+fn parameter_cursor(parameter: &Parameter) -> Option<CommandCursor> {
+    let name = parameter.parameter_data_ref().name.to_string();
+    match parameter {
+        Parameter::Header { .. } => Some(CommandCursor::Header(name)),
+        Parameter::Query { .. } => Some(CommandCursor::Query(name)),
+        Parameter::Cookie { .. } => Some(CommandCursor::Cookie(name)),
+        Parameter::Path { .. } => None,
+    }
+}
+
+/// The parameters the user can choose to fill in next, for `--candidates`.
+///
+/// When the cursor is on a parameter with an `enum`, the candidates are its values instead.
+fn parameter_candidates(
+    focus: &Option<focus::Focus>,
+    parameters: &BTreeMap<&String, &Parameter>,
+    parsed_request: &mut curl_parser::ParsedRequest,
+    curl_options: &mut options::CurlOptions,
+    components: &Option<Components>,
+    original_buffer: &str,
+    command_range: Range<usize>,
+) -> Vec<Candidate> {
+    let focused = focus
+        .as_ref()
+        .and_then(|focus| focused_parameter(focus, parameters));
+    let enum_values = focused
+        .and_then(|parameter| example::parameter_schema(parameter.parameter_data_ref(), components))
+        .map(example::enum_values)
+        .unwrap_or_default();
+    let choices: Vec<(&Parameter, Option<String>)> = match focused {
+        Some(parameter) if !enum_values.is_empty() => enum_values
+            .iter()
+            .map(|value| (parameter, Some(example::value_to_string(value))))
+            .collect(),
+        _ => parameters
+            .values()
+            .filter(|parameter| !matches!(parameter, Parameter::Path { .. }))
+            .filter(|parameter| {
+                parameter_value(parameter, parsed_request, &curl_options.cookies)
+                    .is_none_or(|value| value.is_empty())
+            })
+            .map(|parameter| (*parameter, None))
+            .collect(),
+    };
+
+    let mut candidates = Vec::new();
+    for (parameter, value) in choices {
+        let parameter_data = parameter.parameter_data_ref();
+        let previous = parameter_value(parameter, parsed_request, &curl_options.cookies);
+        let label = match &value {
+            Some(value) => {
+                set_parameter(
+                    parameter,
+                    Some(value),
+                    parsed_request,
+                    &mut curl_options.cookies,
+                );
+                value.to_string()
+            }
+            None => {
+                insert_parameter(
+                    parameter,
+                    parsed_request,
+                    &mut curl_options.cookies,
+                    components,
+                );
+                parameter_data.name.to_string()
+            }
+        };
+        candidates.push(candidate_for(
+            label,
+            parameter_data.description.clone(),
+            parsed_request,
+            curl_options,
+            original_buffer,
+            command_range.clone(),
+            parameter_cursor(parameter),
+        ));
+        set_parameter(
+            parameter,
+            previous.as_deref(),
+            parsed_request,
+            &mut curl_options.cookies,
+        );
+    }
+    candidates
+}
+
+/// Add a credential to the request unless it already has one.
+///
+/// The arguments of credentials that reference the configured environment variables are
+/// recorded, so only they leave the variables for the shell to expand.
+fn insert_credential(
+    credential: security::Credential,
+    parsed_request: &mut curl_parser::ParsedRequest,
+    curl_options: &mut options::CurlOptions,
+) {
+    let argument = match credential {
+        security::Credential::Header { name, value } => {
+            let header_name = http::header::HeaderName::from_str(&name);
+            let header_value = http::header::HeaderValue::from_str(&value);
+            match (header_name, header_value) {
+                (Ok(header_name), Ok(header_value))
+                    if !parsed_request.headers.contains_key(&header_name) =>
+                {
+                    let argument = format!("{}: {}", header_name, value);
+                    parsed_request.headers.insert(header_name, header_value);
+                    argument
+                }
+                _ => return,
+            }
+        }
+        security::Credential::Query { name, value } => {
+            if parsed_request.data_url_encoded.contains_key(&name) {
+                return;
+            }
+            let argument = format!("{}={}", name, value);
+            parsed_request.data_url_encoded.insert(name, value);
+            argument
+        }
+        security::Credential::Cookie { name, value } => {
+            if curl_options.cookies.values.contains_key(&name) {
+                return;
+            }
+            let argument = format!("{}={}", name, value);
+            curl_options.cookies.values.insert(name, value);
+            argument
+        }
+        security::Credential::User(user) => {
+            if curl_options.user.is_some() {
+                return;
+            }
+            curl_options.user = Some(user.clone());
+            user
+        }
+    };
+    let is_reference = curl_options
+        .variables
+        .iter()
+        .any(|variable| argument.contains(&format!("${}", variable)));
+    if is_reference {
+        curl_options.references.push(argument);
+    }
+}
+
 #[derive(Debug)]
 enum EmptySpecParameter {
     Header(String),
     Query(String),
+    Cookie(String),
 }
 fn get_first_empty_spec_parameter(
     parameters: &BTreeMap<&String, &Parameter>,
     parsed_request: &curl_parser::ParsedRequest,
+    cookies: &cookie::Cookies,
+    components: &Option<Components>,
 ) -> Option<EmptySpecParameter> {
     for (_, param) in parameters.iter() {
         if let Parameter::Header { parameter_data, .. } = param {
             let name = &parameter_data.name;
             if let Some(value) = parsed_request.headers.get(name) {
-                if !value.is_empty() {
+                if !is_placeholder_value(param, value.to_str().unwrap_or_default(), components) {
                     // If the header is already set, skip it
                     continue;
                 }
@@ -417,94 +1562,573 @@ fn get_first_empty_spec_parameter(
         } else if let Parameter::Query { parameter_data, .. } = param {
             let name = &parameter_data.name;
             if let Some(value) = parsed_request.data_url_encoded.get(name) {
-                if !value.is_empty() {
+                if !is_placeholder_value(param, value, components) {
                     // If the query parameter is already set, skip it
                     continue;
                 }
                 return Some(EmptySpecParameter::Query(name.to_string()));
             }
+        } else if let Parameter::Cookie { parameter_data, .. } = param {
+            let name = &parameter_data.name;
+            if let Some(value) = cookies.values.get(name) {
+                if !is_placeholder_value(param, value, components) {
+                    // If the cookie is already set, skip it
+                    continue;
+                }
+                return Some(EmptySpecParameter::Cookie(name.to_string()));
+            }
         }
     }
     None
 }
 
-fn print_result_and_exit(
+/// The spec parameter the cursor is on.
+fn focused_parameter<'a>(
+    focus: &focus::Focus,
+    parameters: &BTreeMap<&String, &'a Parameter>,
+) -> Option<&'a Parameter> {
+    parameters
+        .iter()
+        .find(|(name, parameter)| match (focus, parameter) {
+            (focus::Focus::Header(focused), Parameter::Header { .. }) => {
+                focused.eq_ignore_ascii_case(name)
+            }
+            (focus::Focus::Query(focused), Parameter::Query { .. })
+            | (focus::Focus::Cookie(focused), Parameter::Cookie { .. }) => focused == **name,
+            _ => false,
+        })
+        .map(|(_, parameter)| *parameter)
+}
+
+/// The spec parameter the cursor is on, when it has no value of the user's yet and can be
+/// cycled.
+fn focused_spec_parameter(
+    focus: &focus::Focus,
+    parameters: &BTreeMap<&String, &Parameter>,
+    parsed_request: &curl_parser::ParsedRequest,
+    cookies: &cookie::Cookies,
+    components: &Option<Components>,
+) -> Option<EmptySpecParameter> {
+    let parameter = focused_parameter(focus, parameters)?;
+    let value = parameter_value(parameter, parsed_request, cookies)?;
+    if !is_placeholder_value(parameter, &value, components) {
+        return None;
+    }
+    let name = parameter.parameter_data_ref().name.to_string();
+    match parameter {
+        Parameter::Header { .. } => Some(EmptySpecParameter::Header(name)),
+        Parameter::Query { .. } => Some(EmptySpecParameter::Query(name)),
+        Parameter::Cookie { .. } => Some(EmptySpecParameter::Cookie(name)),
+        Parameter::Path { .. } => None,
+    }
+}
+
+/// Whether a parameter's value is one ding filled in rather than the user, so the parameter
+/// can still be swapped for another one.
+///
+/// That's an empty value, or the value the specification suggests for a parameter without an
+/// `enum`. Parameters with an `enum` cycle through its values instead.
+fn is_placeholder_value(
+    parameter: &Parameter,
+    value: &str,
+    components: &Option<Components>,
+) -> bool {
+    if value.is_empty() {
+        return true;
+    }
+    let parameter_data = parameter.parameter_data_ref();
+    let has_enum = example::parameter_schema(parameter_data, components)
+        .is_some_and(|schema| !example::enum_values(schema).is_empty());
+    !has_enum
+        && example::parameter_value(parameter_data, components)
+            .is_some_and(|suggested| example::value_to_string(&suggested) == value)
+}
+
+/// The value after `current` in the parameter's `enum`, starting over after the last one.
+///
+/// Returns `None` when the parameter has no `enum`.
+fn next_enum_value(
+    parameter: &Parameter,
+    current: &str,
+    components: &Option<Components>,
+) -> Option<String> {
+    let values = example::parameter_schema(parameter.parameter_data_ref(), components)
+        .map(example::enum_values)
+        .unwrap_or_default()
+        .iter()
+        .map(example::value_to_string)
+        .collect::<Vec<_>>();
+    let next = values
+        .iter()
+        .position(|value| value == current)
+        .map_or(0, |index| (index + 1) % values.len());
+    values.get(next).cloned()
+}
+
+/// Where the cursor goes to stay on a focused parameter.
+fn focus_cursor(focus: &focus::Focus) -> Option<CommandCursor> {
+    match focus {
+        focus::Focus::Header(name) => Some(CommandCursor::Header(name.to_string())),
+        focus::Focus::Query(name) => Some(CommandCursor::Query(name.to_string())),
+        focus::Focus::Cookie(name) => Some(CommandCursor::Cookie(name.to_string())),
+        focus::Focus::Body(property) => Some(CommandCursor::Body(property.clone())),
+        focus::Focus::Method => Some(CommandCursor::Method),
+        focus::Focus::Url => None,
+    }
+}
+
+/// Replace placeholder segments in `path` with values for the matching path parameters.
+///
+/// A placeholder is a literal template segment such as `{petId}`, or the empty `{}` appended
+/// for a trailing slash. Returns the new path and the offset just past the first substituted
+/// segment, or `None` when the path has no placeholders.
+fn substitute_path_parameters(
+    path: &str,
+    template: &str,
+    path_parameters: &[&Parameter],
+    components: &Option<Components>,
+) -> Option<(String, usize)> {
+    let is_placeholder = |segment: &str| segment.starts_with('{') && segment.ends_with('}');
+    let mut segments = Vec::new();
+    let mut cursor = None;
+    let mut offset = 0;
+    for (segment, template_segment) in path.split('/').zip(template.split('/')) {
+        let mut segment = segment.to_string();
+        if is_placeholder(segment.as_str()) && is_placeholder(template_segment) {
+            let name = &template_segment[1..template_segment.len() - 1];
+            let value = path_parameters
+                .iter()
+                .map(|parameter| parameter.parameter_data_ref())
+                .find(|parameter_data| parameter_data.name == name)
+                .and_then(|parameter_data| example::parameter_value(parameter_data, components))
+                .map(|value| example::value_to_string(&value))
+                .filter(|value| !value.is_empty());
+            segment = value.unwrap_or_else(|| format!("{{{}}}", name));
+            if cursor.is_none() {
+                cursor = Some(offset + segment.len());
+            }
+        }
+        offset += segment.len() + 1;
+        segments.push(segment);
+    }
+    cursor.map(|cursor| (segments.join("/"), cursor))
+}
+
+/// Byte offset where the path starts in a URL such as `https://localhost:9000/pets`.
+fn url_path_start(url: &str) -> usize {
+    match url.find("://") {
+        Some(scheme_end) => url[scheme_end + 3..]
+            .find('/')
+            .map(|authority_end| scheme_end + 3 + authority_end)
+            .unwrap_or(url.len()),
+        None => 0,
+    }
+}
+
+/// The buffer with the curl command rewritten to send `request`, and the character offset of
+/// the cursor in it.
+fn render(
     request: &curl_parser::ParsedRequest,
-    json_out: bool,
+    curl_options: &options::CurlOptions,
     original_buffer: &str,
-    command_position: usize,
-) {
-    let no_body_with_query_parameters =
-        request.body().is_none() && !request.data_url_encoded.is_empty();
+    command_range: Range<usize>,
+    cursor: Option<&CommandCursor>,
+) -> (String, usize) {
+    let has_body = request.body().is_some() || !curl_options.form.is_empty();
+    let no_body_with_query_parameters = !has_body && !request.data_url_encoded.is_empty();
     let format_dash_dash_get = if no_body_with_query_parameters {
         "-G "
     } else {
         ""
     };
-    let mut request_out = format!(
-        "curl -X {} {}{}",
-        request.method, format_dash_dash_get, request.url
-    );
+    // -G would send the body as query parameters too, so with a body they're part of the URL
+    let query_in_url = has_body && !request.data_url_encoded.is_empty();
+    let raw_url = match query_in_url {
+        true => {
+            let query = request
+                .data_url_encoded
+                .iter()
+                .map(|(k, v)| format!("{}={}", encode_query(k), encode_query(v)))
+                .collect::<Vec<_>>()
+                .join("&");
+            let separator = if request.url.query().is_some() {
+                '&'
+            } else {
+                '?'
+            };
+            format!("{}{}{}", request.url, separator, query)
+        }
+        false => request.url.to_string(),
+    };
+    let has_reference = query_in_url
+        && request
+            .data_url_encoded
+            .iter()
+            .any(|(k, v)| curl_options.references.contains(&format!("{}={}", k, v)));
+    let url = match has_reference {
+        true => quote::quote_with_variables(&raw_url, &curl_options.variables),
+        false => quote::quote_if_needed(&raw_url),
+    };
+    let mut request_out = format!("curl -X {} {}{}", request.method, format_dash_dash_get, url);
     for (h, v) in request.headers.iter() {
-        let header_value = match v.is_empty() || v.to_str().unwrap_or("") == "\"\"" {
-            true => "",
-            false => v.to_str().unwrap_or(""),
-        };
-        let header = format!("-H \"{}: {}\"", h, header_value);
-        request_out.push_str(&format!(" {}", header));
+        let header = format!("{}: {}", h, v.to_str().unwrap_or(""));
+        request_out.push_str(&format!(" -H {}", curl_options.quote(&header)));
+    }
+    for option in curl_options.to_args() {
+        request_out.push_str(&format!(" {}", option));
     }
     if let Some(body) = request.body() {
         let body_str = body.to_string();
-        let value = serde_json::to_string_pretty(
-            &serde_json::from_str::<serde_json::Value>(&body_str).unwrap_or_default(),
-        )
-        .unwrap_or_else(|_| "{}".to_string());
-        request_out.push_str(&format!(" -d '{}'", value));
-    } else if !request.data_url_encoded.is_empty() {
+        // JSON bodies are pretty printed, anything else is sent as written
+        let value = match serde_json::from_str::<serde_json::Value>(&body_str) {
+            Ok(value) => serde_json::to_string_pretty(&value).unwrap_or(body_str),
+            Err(_) => body_str,
+        };
+        request_out.push_str(&format!(" -d {}", quote::quote(&value)));
+    } else if no_body_with_query_parameters {
         let data: Vec<String> = request
             .data_url_encoded
             .iter()
-            .map(|(k, v)| format!("--data-urlencode '{}={}'", k, v))
+            .map(|(k, v)| {
+                format!(
+                    "--data-urlencode {}",
+                    curl_options.quote(&format!("{}={}", k, v))
+                )
+            })
             .collect();
         request_out.push_str(&format!(" {}", data.join(" ")));
     }
-    let mut with_cursor_position = request_out.len() - 1;
-    let mut commands_slice = original_buffer
-        .split('|')
-        .map(|c| c.trim())
-        .collect::<Vec<_>>();
-    commands_slice[command_position] = &request_out;
-    let padding_string = " | ";
-    let request_out = commands_slice.join(padding_string);
-    for cmd in commands_slice[..command_position].iter() {
-        with_cursor_position += cmd.len();
-    }
-    if json_out {
-        let metadata = OutputMetadata {
-            cursor_position: with_cursor_position,
-            stdout: request_out,
-            error: None,
-        };
-        let json_output =
-            serde_json::to_string(&metadata).expect("Failed to serialize output metadata to JSON");
-        std::io::stdout()
-            .write_all(json_output.as_bytes())
-            .expect("Failed to write JSON output to stdout");
-    } else {
-        std::io::stdout()
-            .write_all(request_out.as_bytes())
-            .expect("Failed to write to stdout");
+    let with_cursor_position = match cursor {
+        Some(CommandCursor::Method) => format!("curl -X {}", request.method).len(),
+        Some(CommandCursor::Url(url_cursor)) => {
+            // Past the opening quote when the URL had to be quoted
+            let quote_len = url.len() - raw_url.len();
+            format!("curl -X {} {}", request.method, format_dash_dash_get).len()
+                + quote_len.min(1)
+                + *url_cursor
+        }
+        Some(CommandCursor::Header(name)) => {
+            value_end(&request_out, "-H", &format!("{}: ", name.to_lowercase()))
+        }
+        Some(CommandCursor::Query(name)) if query_in_url => {
+            let url_start = format!("curl -X {} ", request.method).len();
+            query_value_end(&request_out, url_start, name)
+        }
+        Some(CommandCursor::Query(name)) => {
+            value_end(&request_out, "--data-urlencode", &format!("{}=", name))
+        }
+        Some(CommandCursor::Cookie(name)) => value_end(&request_out, "-b", &format!("{}=", name)),
+        Some(CommandCursor::Body(property)) => body_value_end(&request_out, property.as_deref()),
+        None => request_out.len() - 1,
+    };
+    // Only the arguments that changed are rewritten, the rest is kept as it was typed
+    let (request_out, with_cursor_position) = rewrite::rewrite(
+        &original_buffer[command_range.clone()],
+        &request_out,
+        cursor.is_some().then_some(with_cursor_position),
+    );
+    let with_cursor_position =
+        command_range.start + with_cursor_position.unwrap_or(request_out.len() - 1);
+    let request_out = format!(
+        "{}{}{}",
+        &original_buffer[..command_range.start],
+        request_out,
+        &original_buffer[command_range.end..]
+    );
+    // Like `--cursor`, the position is counted in characters
+    let with_cursor_position = request_out
+        .char_indices()
+        .take_while(|(offset, _)| *offset < with_cursor_position)
+        .count();
+    (request_out, with_cursor_position)
+}
+
+fn print_result_and_exit(
+    request: &curl_parser::ParsedRequest,
+    output_format: OutputFormat,
+    curl_options: &options::CurlOptions,
+    original_buffer: &str,
+    command_range: Range<usize>,
+    hints: OutputHints,
+) {
+    let (request_out, with_cursor_position) = render(
+        request,
+        curl_options,
+        original_buffer,
+        command_range.clone(),
+        hints.cursor.as_ref(),
+    );
+    match output_format {
+        OutputFormat::Json => {
+            let metadata = OutputMetadata {
+                cursor_position: with_cursor_position,
+                stdout: request_out,
+                error: None,
+                candidates: hints.candidates,
+            };
+            let json_output = serde_json::to_string(&metadata)
+                .expect("Failed to serialize output metadata to JSON");
+            std::io::stdout()
+                .write_all(json_output.as_bytes())
+                .expect("Failed to write JSON output to stdout");
+        }
+        OutputFormat::Candidates | OutputFormat::JsonCandidates => {
+            // There's nothing to choose from, so the command itself is the only candidate
+            let command_end = command_range.end + request_out.len() - original_buffer.len();
+            let label = request_out[command_range.start..command_end]
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_string();
+            let candidate = Candidate {
+                label,
+                description: None,
+                cursor_position: with_cursor_position,
+                stdout: request_out,
+            };
+            print_candidates_and_exit(vec![candidate], output_format);
+        }
+        OutputFormat::Text | OutputFormat::Widget => {
+            if output_format == OutputFormat::Widget {
+                println!("{}", with_cursor_position);
+            }
+            std::io::stdout()
+                .write_all(request_out.as_bytes())
+                .expect("Failed to write to stdout");
+            if !hints.candidates.is_empty() {
+                io::stderr()
+                    .write_all(hints.candidates.join("\n").as_bytes())
+                    .unwrap_or_default();
+            }
+        }
     }
 
     std::process::exit(0);
 }
-fn print_error(buffer: &str, message: &str, json_out: bool) {
-    match json_out {
-        true => {
+/// A candidate for `--candidates` that completes the command to `request`.
+fn candidate_for(
+    label: String,
+    description: Option<String>,
+    request: &curl_parser::ParsedRequest,
+    curl_options: &options::CurlOptions,
+    original_buffer: &str,
+    command_range: Range<usize>,
+    cursor: Option<CommandCursor>,
+) -> Candidate {
+    let (stdout, cursor_position) = render(
+        request,
+        curl_options,
+        original_buffer,
+        command_range,
+        cursor.as_ref(),
+    );
+    Candidate {
+        label,
+        description,
+        cursor_position,
+        stdout,
+    }
+}
+
+fn print_candidates_and_exit(candidates: Vec<Candidate>, output_format: OutputFormat) {
+    let output = match output_format {
+        OutputFormat::JsonCandidates => {
+            serde_json::to_string(&candidates).expect("Failed to serialize candidates to JSON")
+        }
+        _ => candidates
+            .iter()
+            .map(|candidate| {
+                format!(
+                    "{}\t{}\t{}\t{}\0",
+                    candidate.label,
+                    candidate.description.as_deref().unwrap_or_default(),
+                    candidate.cursor_position,
+                    candidate.stdout
+                )
+            })
+            .collect(),
+    };
+    std::io::stdout()
+        .write_all(output.as_bytes())
+        .expect("Failed to write candidates to stdout");
+    std::process::exit(0);
+}
+
+/// Print the problems `ding lint` found, and exit with 2 when there are any.
+fn print_problems_and_exit(problems: Vec<lint::Problem>, output_format: OutputFormat) {
+    let output = match output_format {
+        OutputFormat::Json | OutputFormat::JsonCandidates => {
+            serde_json::to_string(&problems).expect("Failed to serialize problems to JSON")
+        }
+        _ => problems
+            .iter()
+            .map(|problem| format!("{}: {}\n", problem.rule, problem.message))
+            .collect(),
+    };
+    std::io::stdout()
+        .write_all(output.as_bytes())
+        .expect("Failed to write problems to stdout");
+    std::process::exit(if problems.is_empty() { 0 } else { 2 });
+}
+
+/// Offset just before the closing quote of the `flag` argument whose value starts with
+/// `value_prefix`.
+///
+/// Falls back to the end of the command when there's no such argument.
+fn value_end(command: &str, flag: &str, value_prefix: &str) -> usize {
+    let quoted = quote::quote(value_prefix);
+    let needle = format!("{} {}", flag, &quoted[..quoted.len() - 1]);
+    command
+        .find(&needle)
+        .map(|start| start + flag.len() + 1)
+        .and_then(|start| {
+            options::split_words(&command[start..])
+                .first()
+                .map(|word| start + word.end - 1)
+        })
+        .unwrap_or(command.len() - 1)
+}
+
+/// Offset at the end of a query parameter's value in the URL starting at `url_start`.
+fn query_value_end(command: &str, url_start: usize, name: &str) -> usize {
+    let name = encode_query(name);
+    let url = &command[url_start..];
+    let url = &url[..url.find(' ').unwrap_or(url.len())];
+    ["?", "&"]
+        .iter()
+        .find_map(|separator| url.find(&format!("{}{}=", separator, name)))
+        .map(|start| start + name.len() + 2)
+        .map(|value_start| {
+            value_start
+                + url[value_start..]
+                    .find(['&', '\'', '"'])
+                    .unwrap_or(url.len() - value_start)
+        })
+        .map_or(command.len() - 1, |end| url_start + end)
+}
+
+/// Percent-encode a query parameter's name or value for the URL, leaving the characters a
+/// query can hold as they are.
+fn encode_query(component: &str) -> String {
+    let mut encoded = String::with_capacity(component.len());
+    for c in component.chars() {
+        if c.is_ascii_alphanumeric() || "-._~!$()*,;:@/?".contains(c) {
+            encoded.push(c);
+        } else {
+            for byte in c.encode_utf8(&mut [0; 4]).bytes() {
+                encoded.push_str(&format!("%{:02X}", byte));
+            }
+        }
+    }
+    encoded
+}
+
+/// Offset at the end of a pretty printed body property's value, inside the quotes of strings.
+///
+/// Without a property, or when the body doesn't have it, this is the end of the body.
+fn body_value_end(command: &str, property: Option<&str>) -> usize {
+    let Some(body_start) = command.find(" -d '") else {
+        return command.len() - 1;
+    };
+    let key = property.map(|property| format!("\n  {}: ", serde_json::Value::from(property)));
+    let Some(value_start) = key.and_then(|key| {
+        command[body_start..]
+            .find(&key)
+            .map(|start| body_start + start + key.len())
+    }) else {
+        return command.len() - 1;
+    };
+    let line = command[value_start..]
+        .split('\n')
+        .next()
+        .unwrap_or_default();
+    let value = line.trim_end_matches(',');
+    match value.ends_with('"') {
+        true => value_start + value.len() - 1,
+        false => value_start + value.len(),
+    }
+}
+
+/// Read, bundle and deserialize the specification at `spec_path`, along with the paths of
+/// every file it was read from.
+fn read_spec(
+    spec_path: &Path,
+    buffer: &str,
+    output_format: OutputFormat,
+) -> (openapiv3::OpenAPI, Vec<PathBuf>) {
+    let spec_content = std::fs::read_to_string(spec_path);
+    if let Err(e) = spec_content {
+        print_error(
+            buffer,
+            &format!("Failed to read specification file: {}", e),
+            output_format,
+        );
+        std::process::exit(1);
+    }
+    let spec_content = spec_content.unwrap();
+    if spec_content.is_empty() {
+        print_error(buffer, "Specification file is empty", output_format);
+        std::process::exit(1);
+    }
+    let spec = reference::parse(&spec_content, spec_path);
+    if let Err(e) = spec {
+        print_error(
+            buffer,
+            &format!(
+                "Failed to parse specification {}: {}",
+                spec_path.display(),
+                e
+            ),
+            output_format,
+        );
+        std::process::exit(1);
+    }
+    let bundle = reference::bundle(spec.unwrap(), spec_path);
+    if let Err(e) = bundle {
+        print_error(
+            buffer,
+            &format!("Failed to resolve references: {}", e),
+            output_format,
+        );
+        std::process::exit(1);
+    }
+    let (spec, sources) = bundle.unwrap();
+    let is_converted = swagger::is_swagger(&spec) || openapi31::is_openapi31(&spec);
+    let spec = if swagger::is_swagger(&spec) {
+        swagger::convert(spec)
+    } else if openapi31::is_openapi31(&spec) {
+        openapi31::convert(spec)
+    } else {
+        spec
+    };
+    let spec = serde_yaml::from_value::<openapiv3::OpenAPI>(spec);
+    if let Err(e) = spec {
+        // The bundled document has no positions left, so the problem is looked for in the root
+        // document's text. Converted documents don't match their text, and problems in other
+        // files aren't in it.
+        let message = match is_converted {
+            true => None,
+            false => locate::locate(&spec_content, spec_path),
+        }
+        .unwrap_or_else(|| e.to_string());
+        print_error(
+            buffer,
+            &format!("Specification is not a valid OpenAPI document: {}", message),
+            output_format,
+        );
+        std::process::exit(1);
+    }
+    (spec.unwrap(), sources)
+}
+
+fn print_error(buffer: &str, message: &str, output_format: OutputFormat) {
+    match output_format {
+        OutputFormat::Json => {
             let metadata = OutputMetadata {
-                cursor_position: buffer.len() - 1,
+                cursor_position: buffer.chars().count().saturating_sub(1),
                 stdout: buffer.to_string(),
                 error: Some(message.to_string()),
+                candidates: vec![],
             };
             let json_output = serde_json::to_string(&metadata)
                 .expect("Failed to serialize output metadata to JSON");
@@ -512,10 +2136,22 @@ fn print_error(buffer: &str, message: &str, json_out: bool) {
                 .write_all(json_output.as_bytes())
                 .expect("Failed to write JSON output to stdout");
         }
-        false => {
+        OutputFormat::Candidates | OutputFormat::JsonCandidates => {
+            // There are no candidates to offer
+            io::stderr()
+                .write_all(message.as_bytes())
+                .unwrap_or_default();
+            if output_format == OutputFormat::JsonCandidates {
+                io::stdout().write_all(b"[]").unwrap_or_default();
+            }
+        }
+        OutputFormat::Text | OutputFormat::Widget => {
             io::stderr()
                 .write_all(message.as_bytes())
                 .unwrap_or_default();
+            if output_format == OutputFormat::Widget {
+                println!("{}", buffer.chars().count().saturating_sub(1));
+            }
             io::stdout()
                 .write_all(buffer.as_bytes())
                 .unwrap_or_default();
@@ -527,20 +2163,41 @@ pub(crate) trait ReferenceOrExt<T: ComponentLookup> {
     fn item<'a>(&'a self, components: &'a Option<Components>) -> Result<&'a T>;
 }
 pub(crate) trait ComponentLookup: Sized {
+    /// The section of `components` these are defined in
+    const SECTION: &'static str;
     fn get_components(components: &Components) -> &IndexMap<String, ReferenceOr<Self>>;
 }
 impl<T: ComponentLookup> ReferenceOrExt<T> for openapiv3::ReferenceOr<T> {
     fn item<'a>(&'a self, components: &'a Option<Components>) -> Result<&'a T> {
         match self {
             ReferenceOr::Item(item) => Ok(item),
-            ReferenceOr::Reference { reference } => {
-                let idx = reference.rfind('/').unwrap();
-                let key = &reference[idx + 1..];
-                let parameters = T::get_components(components.as_ref().unwrap());
-                parameters
-                    .get(key)
-                    .unwrap_or_else(|| panic!("key {} is missing", key))
-                    .item(components)
+            ReferenceOr::Reference { reference } => lookup_reference(reference, components),
+        }
+    }
+}
+
+pub(crate) fn lookup_reference<'a, T: ComponentLookup>(
+    reference: &str,
+    components: &'a Option<Components>,
+) -> Result<&'a T> {
+    let Some(components) = components else {
+        return Err(reference::ReferenceError::MissingComponents(reference.to_string()).into());
+    };
+    // Components may themselves be references to other components
+    let mut seen = vec![reference.to_string()];
+    loop {
+        let reference = seen.last().unwrap();
+        let component = reference::component_name(reference, T::SECTION)
+            .and_then(|name| T::get_components(components).get(&name));
+        match component {
+            None => return Err(reference::ReferenceError::NotFound(reference.to_string()).into()),
+            Some(ReferenceOr::Item(item)) => return Ok(item),
+            Some(ReferenceOr::Reference { reference }) => {
+                let is_cycle = seen.contains(reference);
+                seen.push(reference.to_string());
+                if is_cycle {
+                    return Err(reference::ReferenceError::Cycle(seen).into());
+                }
             }
         }
     }
@@ -556,34 +2213,71 @@ where
     refs.iter().map(|r| r.item(components))
 }
 
+/// The parameters of an operation, including the ones its path item defines for every operation.
 pub(crate) fn parameter_map<'a>(
-    refs: &'a [ReferenceOr<Parameter>],
+    path_refs: &'a [ReferenceOr<Parameter>],
+    operation_refs: &'a [ReferenceOr<Parameter>],
     components: &'a Option<Components>,
 ) -> Result<BTreeMap<&'a String, &'a Parameter>> {
-    items(refs, components)
-        .map(|res| res.map(|param| (&param.parameter_data_ref().name, param)))
-        .collect()
+    let mut parameters: Vec<&Parameter> = Vec::new();
+    for parameter in items(path_refs, components).chain(items(operation_refs, components)) {
+        let parameter = parameter?;
+        // The operation's parameters override the path's ones with the same name and location
+        parameters.retain(|existing| {
+            existing.parameter_data_ref().name != parameter.parameter_data_ref().name
+                || std::mem::discriminant(*existing) != std::mem::discriminant(parameter)
+        });
+        parameters.push(parameter);
+    }
+    Ok(parameters
+        .into_iter()
+        .map(|param| (&param.parameter_data_ref().name, param))
+        .collect())
+}
+
+impl ComponentLookup for Example {
+    const SECTION: &'static str = "examples";
+
+    fn get_components(components: &Components) -> &IndexMap<String, ReferenceOr<Self>> {
+        &components.examples
+    }
 }
 
 impl ComponentLookup for Parameter {
+    const SECTION: &'static str = "parameters";
+
     fn get_components(components: &Components) -> &IndexMap<String, ReferenceOr<Self>> {
         &components.parameters
     }
 }
 
 impl ComponentLookup for RequestBody {
+    const SECTION: &'static str = "requestBodies";
+
     fn get_components(components: &Components) -> &IndexMap<String, ReferenceOr<Self>> {
         &components.request_bodies
     }
 }
 
 impl ComponentLookup for Response {
+    const SECTION: &'static str = "responses";
+
     fn get_components(components: &Components) -> &IndexMap<String, ReferenceOr<Self>> {
         &components.responses
     }
 }
 
+impl ComponentLookup for SecurityScheme {
+    const SECTION: &'static str = "securitySchemes";
+
+    fn get_components(components: &Components) -> &IndexMap<String, ReferenceOr<Self>> {
+        &components.security_schemes
+    }
+}
+
 impl ComponentLookup for Schema {
+    const SECTION: &'static str = "schemas";
+
     fn get_components(components: &Components) -> &IndexMap<String, ReferenceOr<Self>> {
         &components.schemas
     }
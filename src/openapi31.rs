@@ -0,0 +1,157 @@
+use serde_yaml::{Mapping, Value};
+
+/// Whether the document is an OpenAPI 3.1 specification.
+pub(crate) fn is_openapi31(spec: &Value) -> bool {
+    spec.get("openapi")
+        .and_then(Value::as_str)
+        .is_some_and(|version| version.starts_with("3.1"))
+}
+
+/// Convert an OpenAPI 3.1 specification into the OpenAPI 3.0 document closest to it.
+///
+/// 3.1 schemas are JSON Schema 2020-12. The constructs 3.0 lacks are rewritten into their
+/// 3.0 counterparts: `type` lists into `nullable` and `anyOf`, `examples` into `example`,
+/// `const` into a single `enum` value, numeric `exclusiveMinimum`/`exclusiveMaximum` into
+/// bounds, and `$ref`s with other keys next to them into an `allOf`. `webhooks` describe
+/// requests the API sends rather than receives, so they're left out.
+pub(crate) fn convert(spec: Value) -> Value {
+    let Value::Mapping(mut spec) = spec else {
+        return spec;
+    };
+    spec.insert("openapi".into(), "3.0.3".into());
+    spec.remove("webhooks");
+    spec.remove("jsonSchemaDialect");
+    // Paths are optional in 3.1
+    if !spec.contains_key("paths") {
+        spec.insert("paths".into(), Value::Mapping(Mapping::new()));
+    }
+    if let Some(Value::Mapping(components)) = spec.get_mut("components") {
+        // `$ref`s to path items were inlined when the specification was bundled
+        components.remove("pathItems");
+        if let Some(Value::Mapping(schemas)) = components.get_mut("schemas") {
+            for (_, definition) in schemas.iter_mut() {
+                *definition = schema(std::mem::take(definition));
+            }
+        }
+    }
+    document(Value::Mapping(spec))
+}
+
+/// Convert every schema in the document outside of `components/schemas`.
+fn document(value: Value) -> Value {
+    match value {
+        Value::Mapping(mapping) => {
+            let mapping = mapping
+                .into_iter()
+                .map(|(key, value)| match key.as_str() {
+                    Some("schema") => (key, schema(value)),
+                    Some("schemas") => (key, value),
+                    _ => (key, document(value)),
+                })
+                .collect();
+            Value::Mapping(mapping)
+        }
+        Value::Sequence(items) => Value::Sequence(items.into_iter().map(document).collect()),
+        value => value,
+    }
+}
+
+fn schema(schema: Value) -> Value {
+    let Value::Mapping(schema) = schema else {
+        return schema;
+    };
+    let mut converted = Mapping::new();
+    for (key, value) in schema {
+        match (key.as_str(), value) {
+            (Some("type"), Value::Sequence(types)) => {
+                let (nulls, mut types): (Vec<Value>, Vec<Value>) = types
+                    .into_iter()
+                    .partition(|kind| kind.as_str() == Some("null"));
+                if !nulls.is_empty() {
+                    converted.insert("nullable".into(), true.into());
+                }
+                if types.len() == 1 {
+                    converted.insert(key, types.remove(0));
+                } else if !types.is_empty() {
+                    let any_of = types
+                        .into_iter()
+                        .map(|kind| {
+                            let mut schema = Mapping::new();
+                            schema.insert("type".into(), kind);
+                            Value::Mapping(schema)
+                        })
+                        .collect();
+                    converted.insert("anyOf".into(), Value::Sequence(any_of));
+                }
+            }
+            (Some("type"), Value::String(kind)) if kind == "null" => {
+                converted.insert("nullable".into(), true.into());
+            }
+            (Some("examples"), Value::Sequence(mut examples)) => {
+                if !examples.is_empty() && !converted.contains_key("example") {
+                    converted.insert("example".into(), examples.remove(0));
+                }
+            }
+            (Some("example"), value) => {
+                // `example` takes precedence over the first of `examples`
+                converted.insert(key, value);
+            }
+            (Some("const"), value) => {
+                converted.insert("enum".into(), Value::Sequence(vec![value]));
+            }
+            (Some(bound @ ("exclusiveMinimum" | "exclusiveMaximum")), Value::Number(limit)) => {
+                let inclusive = match bound {
+                    "exclusiveMinimum" => "minimum",
+                    _ => "maximum",
+                };
+                converted.insert(inclusive.into(), Value::Number(limit));
+                converted.insert(key, true.into());
+            }
+            (Some("contentEncoding"), _) if !converted.contains_key("format") => {
+                converted.insert("format".into(), "byte".into());
+            }
+            (Some("contentMediaType"), _) if !converted.contains_key("format") => {
+                converted.insert("format".into(), "binary".into());
+            }
+            (Some("properties" | "patternProperties"), Value::Mapping(properties)) => {
+                let properties = properties
+                    .into_iter()
+                    .map(|(name, property)| (name, self::schema(property)))
+                    .collect();
+                converted.insert(key, Value::Mapping(properties));
+            }
+            (Some("allOf" | "anyOf" | "oneOf"), Value::Sequence(schemas)) => {
+                let schemas: Vec<Value> = schemas
+                    .into_iter()
+                    .filter(|schema| {
+                        let is_null = schema.get("type").and_then(Value::as_str) == Some("null");
+                        if is_null {
+                            converted.insert("nullable".into(), true.into());
+                        }
+                        !is_null
+                    })
+                    .map(self::schema)
+                    .collect();
+                converted.insert(key, Value::Sequence(schemas));
+            }
+            (Some("items" | "additionalProperties" | "not"), value) => {
+                converted.insert(key, self::schema(value));
+            }
+            (_, value) => {
+                converted.insert(key, value);
+            }
+        }
+    }
+    // OpenAPI 3.0 ignores everything next to a `$ref`, but not next to an `allOf`
+    if converted.contains_key("$ref") && converted.len() > 1 {
+        let mut reference = Mapping::new();
+        reference.insert("$ref".into(), converted.remove("$ref").unwrap_or_default());
+        let mut all_of = match converted.remove("allOf") {
+            Some(Value::Sequence(all_of)) => all_of,
+            _ => vec![],
+        };
+        all_of.insert(0, Value::Mapping(reference));
+        converted.insert("allOf".into(), Value::Sequence(all_of));
+    }
+    Value::Mapping(converted)
+}
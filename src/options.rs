@@ -0,0 +1,420 @@
+use std::ops::Range;
+
+use crate::cookie::{self, Cookies};
+use crate::form::{self, Form};
+use crate::quote;
+
+/// Options of a curl command that the curl parser doesn't understand.
+#[derive(Debug, Default)]
+pub(crate) struct CurlOptions {
+    pub(crate) cookies: Cookies,
+    pub(crate) form: Form,
+    /// `-u`/`--user` credentials
+    pub(crate) user: Option<String>,
+    /// Environment variables credentials reference, left for the shell to expand
+    pub(crate) variables: Vec<String>,
+    /// Arguments of the credentials ding added that reference the variables, such as
+    /// `authorization: Bearer $API_TOKEN`
+    pub(crate) references: Vec<String>,
+}
+
+impl CurlOptions {
+    /// The arguments to emit after the headers.
+    pub(crate) fn to_args(&self) -> Vec<String> {
+        self.user
+            .iter()
+            .map(|user| format!("-u {}", self.quote(user)))
+            .chain(self.cookies.to_args(|cookie| self.quote(cookie)))
+            .chain(self.form.to_args())
+            .collect()
+    }
+
+    /// Quote an argument, leaving the variables of the credentials ding added to the shell.
+    pub(crate) fn quote(&self, argument: &str) -> String {
+        match self
+            .references
+            .iter()
+            .any(|reference| reference == argument)
+        {
+            true => quote::quote_with_variables(argument, &self.variables),
+            false => quote::quote(argument),
+        }
+    }
+}
+
+/// Split the options the curl parser doesn't understand out of a curl command.
+pub(crate) fn extract(command: &str) -> (String, CurlOptions) {
+    let (command, cookies) = cookie::extract_cookies(command);
+    let (command, form) = form::extract_form(&command);
+    let (command, users) = extract_option(&command, "-u", "--user");
+    let command = strip_unparsed(&command);
+    let curl_options = CurlOptions {
+        cookies,
+        form,
+        // Like curl, the last -u wins
+        user: users.into_iter().last(),
+        variables: Vec::new(),
+        references: Vec::new(),
+    };
+    (command, curl_options)
+}
+
+/// Remove every occurrence of an option from a curl command.
+///
+/// The curl parser doesn't understand some options, so they are split out before the command
+/// is parsed. `-b 'a=b'`, `-b'a=b'`, `--cookie 'a=b'` and `--cookie='a=b'` are all recognised.
+/// Returns the remaining command and the unquoted values of the removed options.
+pub(crate) fn extract_option(command: &str, short: &str, long: &str) -> (String, Vec<String>) {
+    let mut values = Vec::new();
+    let mut removed: Vec<Range<usize>> = Vec::new();
+    // The values of other options, such as `-d '-b'`, are part of their arguments
+    for argument in arguments(command) {
+        let is_option = argument
+            .flag
+            .as_deref()
+            .is_some_and(|flag| flag == short || flag == long);
+        // A quoted `'-b'` is a word like any other rather than an option
+        let is_quoted = !command[argument.range.start..].starts_with('-');
+        if is_option
+            && !is_quoted
+            && let Some(value) = argument.value
+        {
+            removed.push(argument.range);
+            values.push(value);
+        }
+    }
+
+    let mut stripped = String::with_capacity(command.len());
+    let mut last = 0;
+    for range in removed {
+        stripped.push_str(command[last..range.start].trim_end());
+        last = range.end;
+    }
+    stripped.push_str(&command[last..]);
+    (stripped, values)
+}
+
+/// Remove the options the curl parser doesn't understand, such as `-s` or `--retry 3`.
+///
+/// They aren't needed to complete the request, and are kept when the command is rewritten.
+fn strip_unparsed(command: &str) -> String {
+    let mut stripped = String::with_capacity(command.len());
+    let mut last = 0;
+    for argument in arguments(command) {
+        let Some(flag) = argument.flag else {
+            continue;
+        };
+        if !PARSED_OPTIONS.contains(&flag.as_str()) {
+            stripped.push_str(command[last..argument.range.start].trim_end());
+            last = argument.range.end;
+        }
+    }
+    stripped.push_str(&command[last..]);
+    stripped
+}
+
+/// Options the curl parser understands.
+const PARSED_OPTIONS: [&str; 11] = [
+    "-X",
+    "--request",
+    "-H",
+    "--header",
+    "-d",
+    "--data",
+    "--data-raw",
+    "--data-binary",
+    "--data-urlencode",
+    "-G",
+    "--get",
+];
+
+/// One argument of a curl command: an option with its value, or a word on its own.
+pub(crate) struct Argument {
+    /// The option, such as `-H` or `--header`, or `None` for a word that isn't an option
+    pub(crate) flag: Option<String>,
+    /// The unquoted value, or the word itself when there's no flag
+    pub(crate) value: Option<String>,
+    /// Where the argument is in the command, including its value
+    pub(crate) range: Range<usize>,
+}
+
+/// Group the words of a curl command after `curl` itself into arguments.
+///
+/// Values can be separate words (`-X POST`), attached to short options (`-XPOST`) or joined to
+/// long options with `=` (`--request=POST`).
+pub(crate) fn arguments(command: &str) -> Vec<Argument> {
+    let words = split_words(command);
+    let mut arguments = Vec::new();
+    let mut i = 1;
+    while i < words.len() {
+        let word = &words[i];
+        let short_flag = word.value.get(..2).filter(|flag| *flag != "--");
+        let argument = if !word.value.starts_with('-') || word.value == "-" {
+            Argument {
+                flag: None,
+                value: Some(word.value.to_string()),
+                range: word.start..word.end,
+            }
+        } else if takes_value(&word.value) {
+            let value = words.get(i + 1);
+            if value.is_some() {
+                i += 1;
+            }
+            Argument {
+                flag: Some(word.value.to_string()),
+                value: value.map(|value| value.value.to_string()),
+                range: word.start..value.map_or(word.end, |value| value.end),
+            }
+        } else if let Some((flag, value)) = word.value.split_once('=')
+            && flag.starts_with("--")
+            && takes_value(flag)
+        {
+            Argument {
+                flag: Some(flag.to_string()),
+                value: Some(value.to_string()),
+                range: word.start..word.end,
+            }
+        } else if let Some(flag) = short_flag
+            && takes_value(flag)
+        {
+            Argument {
+                flag: Some(flag.to_string()),
+                value: Some(word.value[2..].to_string()),
+                range: word.start..word.end,
+            }
+        } else {
+            Argument {
+                flag: Some(word.value.to_string()),
+                value: None,
+                range: word.start..word.end,
+            }
+        };
+        arguments.push(argument);
+        i += 1;
+    }
+    arguments
+}
+
+/// Whether a curl flag takes the next word as its value, as listed by `curl --help all`.
+pub(crate) fn takes_value(flag: &str) -> bool {
+    // `--expand-data` and the like are the same options with variables expanded in the value
+    let flag = match flag.strip_prefix("--expand-") {
+        Some(name) => &format!("--{}", name),
+        None => flag,
+    };
+    matches!(
+        flag,
+        "--abstract-unix-socket"
+            | "--alt-svc"
+            | "--aws-sigv4"
+            | "--cacert"
+            | "--capath"
+            | "-E"
+            | "--cert"
+            | "--cert-type"
+            | "--ciphers"
+            | "-K"
+            | "--config"
+            | "--connect-timeout"
+            | "--connect-to"
+            | "-C"
+            | "--continue-at"
+            | "-b"
+            | "--cookie"
+            | "-c"
+            | "--cookie-jar"
+            | "--create-file-mode"
+            | "--crlfile"
+            | "--curves"
+            | "-d"
+            | "--data"
+            | "--data-ascii"
+            | "--data-binary"
+            | "--data-raw"
+            | "--data-urlencode"
+            | "--delegation"
+            | "--dns-interface"
+            | "--dns-ipv4-addr"
+            | "--dns-ipv6-addr"
+            | "--dns-servers"
+            | "--doh-url"
+            | "-D"
+            | "--dump-header"
+            | "--ech"
+            | "--egd-file"
+            | "--engine"
+            | "--etag-compare"
+            | "--etag-save"
+            | "--expect100-timeout"
+            | "-F"
+            | "--form"
+            | "--form-string"
+            | "--ftp-account"
+            | "--ftp-alternative-to-user"
+            | "--ftp-method"
+            | "-P"
+            | "--ftp-port"
+            | "--ftp-ssl-ccc-mode"
+            | "--happy-eyeballs-timeout-ms"
+            | "--haproxy-clientip"
+            | "-H"
+            | "--header"
+            | "--hostpubmd5"
+            | "--hostpubsha256"
+            | "--hsts"
+            | "--interface"
+            | "--ip-tos"
+            | "--ipfs-gateway"
+            | "--json"
+            | "--keepalive-time"
+            | "--key"
+            | "--key-type"
+            | "--knownhosts"
+            | "--krb"
+            | "--libcurl"
+            | "--limit-rate"
+            | "--local-port"
+            | "--login-options"
+            | "--mail-auth"
+            | "--mail-from"
+            | "--mail-rcpt"
+            | "--max-filesize"
+            | "--max-redirs"
+            | "-m"
+            | "--max-time"
+            | "--netrc-file"
+            | "--noproxy"
+            | "--oauth2-bearer"
+            | "-o"
+            | "--output"
+            | "--output-dir"
+            | "--parallel-max"
+            | "--pass"
+            | "--pinnedpubkey"
+            | "--preproxy"
+            | "--proto"
+            | "--proto-default"
+            | "--proto-redir"
+            | "-x"
+            | "--proxy"
+            | "--proxy-cacert"
+            | "--proxy-capath"
+            | "--proxy-cert"
+            | "--proxy-cert-type"
+            | "--proxy-ciphers"
+            | "--proxy-crlfile"
+            | "--proxy-header"
+            | "--proxy-key"
+            | "--proxy-key-type"
+            | "--proxy-pass"
+            | "--proxy-pinnedpubkey"
+            | "--proxy-service-name"
+            | "--proxy-tls13-ciphers"
+            | "--proxy-tlsauthtype"
+            | "--proxy-tlspassword"
+            | "--proxy-tlsuser"
+            | "-U"
+            | "--proxy-user"
+            | "--pubkey"
+            | "-Q"
+            | "--quote"
+            | "--random-file"
+            | "-r"
+            | "--range"
+            | "--rate"
+            | "-e"
+            | "--referer"
+            | "-X"
+            | "--request"
+            | "--request-target"
+            | "--resolve"
+            | "--retry"
+            | "--retry-delay"
+            | "--retry-max-time"
+            | "--sasl-authzid"
+            | "--service-name"
+            | "--sigalgs"
+            | "--socks4"
+            | "--socks4a"
+            | "--socks5"
+            | "--socks5-gssapi-service"
+            | "--socks5-hostname"
+            | "-Y"
+            | "--speed-limit"
+            | "-y"
+            | "--speed-time"
+            | "--ssl-sessions"
+            | "--stderr"
+            | "-t"
+            | "--telnet-option"
+            | "--tftp-blksize"
+            | "-z"
+            | "--time-cond"
+            | "--tls-max"
+            | "--tls13-ciphers"
+            | "--tlsauthtype"
+            | "--tlspassword"
+            | "--tlsuser"
+            | "--trace"
+            | "--trace-ascii"
+            | "--trace-config"
+            | "--unix-socket"
+            | "-T"
+            | "--upload-file"
+            | "--upload-flags"
+            | "--url"
+            | "--url-query"
+            | "-u"
+            | "--user"
+            | "-A"
+            | "--user-agent"
+            | "--variable"
+            | "--vlan-priority"
+            | "-w"
+            | "--write-out"
+    )
+}
+
+pub(crate) struct Word {
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+    /// The word with quotes and escapes removed
+    pub(crate) value: String,
+}
+
+/// Split a command into words the way a shell would, keeping each word's byte range.
+pub(crate) fn split_words(command: &str) -> Vec<Word> {
+    let mut words = Vec::new();
+    let mut current: Option<Word> = None;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    for (i, c) in command.char_indices() {
+        if quote.is_none() && !escaped && c.is_whitespace() {
+            if let Some(word) = current.take() {
+                words.push(word);
+            }
+            continue;
+        }
+        let word = current.get_or_insert_with(|| Word {
+            start: i,
+            end: i,
+            value: String::new(),
+        });
+        word.end = i + c.len_utf8();
+        if escaped {
+            word.value.push(c);
+            escaped = false;
+            continue;
+        }
+        match (quote, c) {
+            (None, '\\') | (Some('"'), '\\') => escaped = true,
+            (None, '\'' | '"') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (_, c) => word.value.push(c),
+        }
+    }
+    if let Some(word) = current {
+        words.push(word);
+    }
+    words
+}
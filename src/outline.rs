@@ -0,0 +1,47 @@
+use indexmap::IndexMap;
+use openapiv3::{OpenAPI, PathItem, ReferenceOr, Server};
+use serde::{Deserialize, Serialize};
+
+/// What's needed of a path item before a path is matched: its template is routed and
+/// completed, and its servers are chosen from.
+///
+/// Large specifications have thousands of path items, so a cached specification only has
+/// their outlines until one is matched.
+#[derive(Default, Deserialize, Serialize)]
+pub(crate) struct Outline {
+    /// The summary of the path, or of its first operation
+    pub(crate) summary: Option<String>,
+    pub(crate) servers: Vec<Server>,
+    /// The servers of the operations that have their own, by method
+    pub(crate) operation_servers: IndexMap<String, Vec<Server>>,
+}
+
+impl Outline {
+    pub(crate) fn new(path_item: &ReferenceOr<PathItem>) -> Outline {
+        let Some(path_item) = path_item.as_item() else {
+            return Outline::default();
+        };
+        Outline {
+            summary: path_item.summary.clone().or_else(|| {
+                path_item
+                    .iter()
+                    .find_map(|(_, operation)| operation.summary.clone())
+            }),
+            servers: path_item.servers.clone(),
+            operation_servers: path_item
+                .iter()
+                .filter(|(_, operation)| !operation.servers.is_empty())
+                .map(|(method, operation)| (method.to_string(), operation.servers.clone()))
+                .collect(),
+        }
+    }
+}
+
+/// The outline of every path item of a specification, by its template.
+pub(crate) fn outlines(spec: &OpenAPI) -> IndexMap<String, Outline> {
+    spec.paths
+        .paths
+        .iter()
+        .map(|(template, path_item)| (template.to_string(), Outline::new(path_item)))
+        .collect()
+}
@@ -0,0 +1,85 @@
+/// Rank the specification's path templates against a partially typed path.
+///
+/// Templates that match segment by segment, with the last typed segment being a prefix, are
+/// preferred over templates that only contain the typed characters in order. Only the best of
+/// those two tiers is returned, shortest first. Path parameter values the user already typed are
+/// kept in place of their placeholders.
+pub(crate) fn complete_path(path: &str, templates: &[String]) -> Vec<String> {
+    let mut candidates = templates
+        .iter()
+        .filter_map(|template| complete_segments(path, template))
+        .collect::<Vec<_>>();
+    if candidates.is_empty() {
+        candidates = templates
+            .iter()
+            .filter(|template| is_subsequence(path, template))
+            .cloned()
+            .collect();
+    }
+    // Stable sort so templates of equal length keep the order of the specification
+    candidates.sort_by_key(|candidate| candidate.len());
+    candidates.dedup();
+    candidates
+}
+
+/// The longest prefix shared by every candidate.
+pub(crate) fn common_prefix(candidates: &[String]) -> String {
+    let Some(first) = candidates.first() else {
+        return String::new();
+    };
+    let mut prefix_len = first.len();
+    for candidate in candidates.iter().skip(1) {
+        prefix_len = first
+            .char_indices()
+            .zip(candidate.chars())
+            .take_while(|((_, a), b)| a == b)
+            .map(|((i, a), _)| i + a.len_utf8())
+            .last()
+            .unwrap_or(0)
+            .min(prefix_len);
+    }
+    first[..prefix_len].to_string()
+}
+
+fn complete_segments(path: &str, template: &str) -> Option<String> {
+    let typed = path.split('/').collect::<Vec<_>>();
+    let template_segments = template.split('/').collect::<Vec<_>>();
+    if typed.len() > template_segments.len() {
+        return None;
+    }
+    let last = typed.len() - 1;
+    let mut completed = Vec::with_capacity(template_segments.len());
+    for (i, template_segment) in template_segments.iter().enumerate() {
+        let is_parameter = template_segment.starts_with('{') && template_segment.ends_with('}');
+        let segment = match typed.get(i) {
+            None => template_segment.to_string(),
+            Some(segment) if is_parameter && !segment.is_empty() => segment.to_string(),
+            Some(_) if is_parameter => template_segment.to_string(),
+            Some(segment) if i == last => {
+                if !template_segment
+                    .to_lowercase()
+                    .starts_with(&segment.to_lowercase())
+                {
+                    return None;
+                }
+                template_segment.to_string()
+            }
+            Some(segment) => {
+                if segment != template_segment {
+                    return None;
+                }
+                segment.to_string()
+            }
+        };
+        completed.push(segment);
+    }
+    Some(completed.join("/"))
+}
+
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut haystack = haystack.chars().flat_map(char::to_lowercase);
+    needle
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|c| haystack.any(|h| h == c))
+}
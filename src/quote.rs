@@ -0,0 +1,51 @@
+/// Quote a word for a POSIX shell.
+///
+/// The word is wrapped in single quotes, which keep everything literal, and single quotes in
+/// it are written as `'\''`.
+pub(crate) fn quote(word: &str) -> String {
+    format!("'{}'", word.replace('\'', "'\\''"))
+}
+
+/// Quote a word for a POSIX shell only when it has characters the shell would interpret.
+pub(crate) fn quote_if_needed(word: &str) -> String {
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "_-.,:/@%+=".contains(c);
+    match !word.is_empty() && word.chars().all(is_safe) {
+        true => word.to_string(),
+        false => quote(word),
+    }
+}
+
+/// Quote a word for a POSIX shell, except for references to the variables, which are left in
+/// double quotes for the shell to expand: `Bearer $TOKEN` becomes `'Bearer '"$TOKEN"`.
+pub(crate) fn quote_with_variables(word: &str, variables: &[String]) -> String {
+    let mut quoted = String::new();
+    let mut literal_start = 0;
+    let mut i = 0;
+    while i < word.len() {
+        let reference = variables.iter().find(|variable| {
+            let rest = &word[i..];
+            let after = rest
+                .strip_prefix('$')
+                .and_then(|rest| rest.strip_prefix(variable.as_str()));
+            // `$NAME_2` is a different variable
+            after.is_some_and(|after| {
+                !after.starts_with(|c: char| c.is_ascii_alphanumeric() || c == '_')
+            })
+        });
+        match reference {
+            Some(variable) => {
+                if literal_start < i {
+                    quoted.push_str(&quote(&word[literal_start..i]));
+                }
+                quoted.push_str(&format!("\"${}\"", variable));
+                i += variable.len() + 1;
+                literal_start = i;
+            }
+            None => i += word[i..].chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    if literal_start < word.len() || quoted.is_empty() {
+        quoted.push_str(&quote(&word[literal_start..]));
+    }
+    quoted
+}
@@ -0,0 +1,449 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeSeed;
+use serde_yaml::{Mapping, Value};
+
+/// Why a `$ref` couldn't be resolved.
+#[derive(Debug)]
+pub(crate) enum ReferenceError {
+    /// The specification has no `components` to look the reference up in
+    MissingComponents(String),
+    /// Nothing exists at the location the reference points to
+    NotFound(String),
+    /// References that only point at each other and never reach a definition
+    Cycle(Vec<String>),
+    /// References to `http://` or `https://` documents
+    Remote(String),
+    /// A referenced file couldn't be read or parsed
+    File { path: PathBuf, message: String },
+}
+
+impl fmt::Display for ReferenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReferenceError::MissingComponents(reference) => write!(
+                f,
+                "{} can't be resolved because the specification has no components",
+                reference
+            ),
+            ReferenceError::NotFound(reference) => write!(f, "{} does not exist", reference),
+            ReferenceError::Cycle(references) => {
+                write!(f, "reference cycle {}", references.join(" -> "))
+            }
+            ReferenceError::Remote(reference) => {
+                write!(f, "remote reference {} is not supported", reference)
+            }
+            ReferenceError::File { path, message } => {
+                write!(f, "failed to load {}: {}", path.display(), message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReferenceError {}
+
+/// Why a document couldn't be parsed, and where.
+#[derive(Debug)]
+pub(crate) struct ParseError {
+    /// `JSON` or `YAML`, whichever the document was parsed as
+    format: &'static str,
+    /// Line and column of the problem, both starting at 1
+    position: Option<(usize, usize)>,
+    message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.position {
+            Some((line, column)) => write!(
+                f,
+                "invalid {} at line {} column {}: {}",
+                self.format, line, column, self.message
+            ),
+            None => write!(f, "invalid {}: {}", self.format, self.message),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl ParseError {
+    pub(crate) fn position(&self) -> Option<(usize, usize)> {
+        self.position
+    }
+
+    pub(crate) fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+/// Inline every `$ref` the typed specification can't follow into the root document.
+///
+/// Only `#/components/<section>/<name>` references in the root document are left alone, except
+/// for OpenAPI 3.1's `pathItems`, which 3.0 doesn't have.
+/// Everything else (other files, deeper pointers, `~0`/`~1` escapes) is replaced by the value
+/// it points to. Files are loaded relative to the file that references them. Structures that
+/// contain themselves are moved into `components/schemas` and referenced from there, so they
+/// are expanded lazily like any other recursive schema.
+///
+/// Returns the bundled document and the paths of every file it was read from, the root first.
+pub(crate) fn bundle(root: Value, path: &Path) -> Result<(Value, Vec<PathBuf>), ReferenceError> {
+    let root_path = canonical(path)?;
+    let mut bundler = Bundler {
+        root_path: root_path.clone(),
+        documents: HashMap::from([(root_path.clone(), root.clone())]),
+        stack: Vec::new(),
+        hoisted: HashMap::new(),
+        hoisted_schemas: Mapping::new(),
+    };
+    let mut root = bundler.resolve(root, &root_path)?;
+    if !bundler.hoisted_schemas.is_empty() {
+        let schemas = root
+            .as_mapping_mut()
+            .map(|root| {
+                root.entry("components".into())
+                    .or_insert_with(|| Mapping::new().into())
+            })
+            .and_then(|components| components.as_mapping_mut())
+            .map(|components| {
+                components
+                    .entry("schemas".into())
+                    .or_insert_with(|| Mapping::new().into())
+            })
+            .and_then(|schemas| schemas.as_mapping_mut());
+        if let Some(schemas) = schemas {
+            schemas.extend(bundler.hoisted_schemas);
+        }
+    }
+    let mut referenced: Vec<PathBuf> = bundler
+        .documents
+        .into_keys()
+        .filter(|path| *path != root_path)
+        .collect();
+    referenced.sort();
+    let sources = std::iter::once(root_path).chain(referenced).collect();
+    Ok((root, sources))
+}
+
+/// Whether a pointer is to a component the typed specification can follow a `$ref` to, that is
+/// `/components/<section>/<name>` in any section but `pathItems`.
+fn is_component(pointer: &str) -> bool {
+    let Some(component) = pointer.strip_prefix("/components/") else {
+        return false;
+    };
+    matches!(
+        component.split('/').collect::<Vec<_>>()[..],
+        [section, _] if section != "pathItems"
+    )
+}
+
+/// The unescaped component name of a `#/components/<section>/<name>` reference.
+pub(crate) fn component_name(reference: &str, section: &str) -> Option<String> {
+    let name = reference
+        .strip_prefix("#/components/")?
+        .strip_prefix(section)?
+        .strip_prefix('/')?;
+    if name.contains('/') {
+        return None;
+    }
+    Some(unescape(&percent_decode(name)))
+}
+
+/// Parse a specification, or a document it references, as JSON or YAML.
+///
+/// `.json`, `.yaml` and `.yml` files are parsed as such. Anything else, like a specification
+/// fetched from a URL without an extension, is JSON when it starts with `{` or `[`.
+pub(crate) fn parse(content: &str, path: &Path) -> Result<Value, ParseError> {
+    parse_seed(content, path, PhantomData)
+}
+
+/// Parse a document like [`parse`], with a seed that decides what to make of it.
+pub(crate) fn parse_seed<'de, S: DeserializeSeed<'de>>(
+    content: &'de str,
+    path: &Path,
+    seed: S,
+) -> Result<S::Value, ParseError> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let is_json = match path.extension().and_then(|extension| extension.to_str()) {
+        Some(extension) if extension.eq_ignore_ascii_case("json") => true,
+        Some(extension)
+            if extension.eq_ignore_ascii_case("yaml") || extension.eq_ignore_ascii_case("yml") =>
+        {
+            false
+        }
+        _ => content.trim_start().starts_with(['{', '[']),
+    };
+    if is_json {
+        let mut deserializer = serde_json::Deserializer::from_str(content);
+        return seed
+            .deserialize(&mut deserializer)
+            .and_then(|value| deserializer.end().map(|_| value))
+            .map_err(|e| {
+                let position = (e.line() > 0).then(|| (e.line(), e.column()));
+                parse_error("JSON", position, e.to_string())
+            });
+    }
+    seed.deserialize(serde_yaml::Deserializer::from_str(content))
+        .map_err(|e| {
+            let position = e
+                .location()
+                .map(|location| (location.line(), location.column()));
+            parse_error("YAML", position, e.to_string())
+        })
+}
+
+/// Both parsers end their message with the position, which is already part of the error.
+fn parse_error(
+    format: &'static str,
+    position: Option<(usize, usize)>,
+    message: String,
+) -> ParseError {
+    let message = match position {
+        Some((line, column)) => {
+            message.replacen(&format!(" at line {} column {}", line, column), "", 1)
+        }
+        None => message,
+    };
+    ParseError {
+        format,
+        position,
+        message,
+    }
+}
+
+struct Frame {
+    /// The file and pointer being inlined, such as `/specs/pet.yaml#/Pet`
+    location: String,
+    /// The reference as written
+    reference: String,
+    /// Whether the target is itself only a `$ref`
+    is_alias: bool,
+}
+
+struct Bundler {
+    root_path: PathBuf,
+    /// Parsed documents by canonical path, including the root
+    documents: HashMap<PathBuf, Value>,
+    stack: Vec<Frame>,
+    /// Component names given to locations that contain themselves
+    hoisted: HashMap<String, String>,
+    hoisted_schemas: Mapping,
+}
+
+impl Bundler {
+    fn resolve(&mut self, value: Value, file: &Path) -> Result<Value, ReferenceError> {
+        match value {
+            Value::Mapping(mapping) => {
+                if let Some(Value::String(reference)) = mapping.get("$ref") {
+                    let is_local_component = file == self.root_path
+                        && reference.strip_prefix('#').is_some_and(is_component);
+                    if is_local_component {
+                        return Ok(Value::Mapping(mapping));
+                    }
+                    let inlined = self.inline(reference, file)?;
+                    return self.with_siblings(inlined, mapping, file);
+                }
+                let mut resolved = Mapping::new();
+                for (key, value) in mapping {
+                    resolved.insert(key, self.resolve(value, file)?);
+                }
+                Ok(Value::Mapping(resolved))
+            }
+            Value::Sequence(items) => items
+                .into_iter()
+                .map(|item| self.resolve(item, file))
+                .collect::<Result<Vec<_>, _>>()
+                .map(Value::Sequence),
+            value => Ok(value),
+        }
+    }
+
+    /// Apply the keys next to a `$ref`, which OpenAPI 3.1 allows, on top of the value it points
+    /// to. They stay next to the reference when the value is a component.
+    fn with_siblings(
+        &mut self,
+        inlined: Value,
+        mut mapping: Mapping,
+        file: &Path,
+    ) -> Result<Value, ReferenceError> {
+        mapping.remove("$ref");
+        let Value::Mapping(mut inlined) = inlined else {
+            return Ok(inlined);
+        };
+        for (key, value) in mapping {
+            inlined.insert(key, self.resolve(value, file)?);
+        }
+        Ok(Value::Mapping(inlined))
+    }
+
+    fn inline(&mut self, reference: &str, file: &Path) -> Result<Value, ReferenceError> {
+        if reference.starts_with("http://") || reference.starts_with("https://") {
+            return Err(ReferenceError::Remote(reference.to_string()));
+        }
+        let (target_file, pointer) = match reference.split_once('#') {
+            Some(("", pointer)) => (file.to_path_buf(), pointer.to_string()),
+            Some((relative, pointer)) => (self.relative(file, relative)?, pointer.to_string()),
+            None => (self.relative(file, reference)?, String::new()),
+        };
+        let pointer = percent_decode(&pointer);
+        if target_file == self.root_path && is_component(&pointer) {
+            // Another file pointing back at a component of the root document
+            return Ok(reference_to(&format!("#{}", pointer)));
+        }
+
+        let location = format!("{}#{}", target_file.display(), pointer);
+        if let Some(position) = self.stack.iter().position(|f| f.location == location) {
+            let cycle = &self.stack[position..];
+            if cycle.iter().all(|frame| frame.is_alias) {
+                let mut references = cycle
+                    .iter()
+                    .map(|frame| frame.reference.to_string())
+                    .collect::<Vec<_>>();
+                references.push(reference.to_string());
+                return Err(ReferenceError::Cycle(references));
+            }
+            let name = self.hoisted_name(&location, &pointer, &target_file);
+            return Ok(reference_to(&format!("#/components/schemas/{}", name)));
+        }
+
+        let target = resolve_pointer(self.document(&target_file)?, &pointer)
+            .cloned()
+            .ok_or_else(|| ReferenceError::NotFound(reference.to_string()))?;
+        let is_alias = matches!(&target, Value::Mapping(mapping) if mapping.contains_key("$ref"));
+        self.stack.push(Frame {
+            location: location.clone(),
+            reference: reference.to_string(),
+            is_alias,
+        });
+        let resolved = self.resolve(target, &target_file);
+        self.stack.pop();
+        let resolved = resolved?;
+        match self.hoisted.get(&location) {
+            Some(name) => {
+                self.hoisted_schemas.insert(name.as_str().into(), resolved);
+                Ok(reference_to(&format!("#/components/schemas/{}", name)))
+            }
+            None => Ok(resolved),
+        }
+    }
+
+    fn hoisted_name(&mut self, location: &str, pointer: &str, file: &Path) -> String {
+        if let Some(name) = self.hoisted.get(location) {
+            return name.to_string();
+        }
+        let base = match pointer.rsplit('/').next() {
+            Some(token) if !token.is_empty() => unescape(token),
+            _ => file
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_else(|| "Schema".to_string()),
+        };
+        let existing = resolve_pointer(&self.documents[&self.root_path], "/components/schemas")
+            .and_then(|schemas| schemas.as_mapping());
+        let is_taken = |name: &str| {
+            existing.is_some_and(|schemas| schemas.contains_key(name))
+                || self.hoisted.values().any(|hoisted| hoisted == name)
+        };
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while is_taken(&name) {
+            name = format!("{}{}", base, suffix);
+            suffix += 1;
+        }
+        self.hoisted.insert(location.to_string(), name.clone());
+        name
+    }
+
+    fn relative(&self, file: &Path, relative: &str) -> Result<PathBuf, ReferenceError> {
+        let directory = file.parent().unwrap_or(Path::new("."));
+        canonical(&directory.join(percent_decode(relative)))
+    }
+
+    fn document(&mut self, path: &Path) -> Result<&Value, ReferenceError> {
+        if !self.documents.contains_key(path) {
+            let document = load(path)?;
+            self.documents.insert(path.to_path_buf(), document);
+        }
+        Ok(&self.documents[path])
+    }
+}
+
+fn load(path: &Path) -> Result<Value, ReferenceError> {
+    let error = |message: String| ReferenceError::File {
+        path: path.to_path_buf(),
+        message,
+    };
+    let content = std::fs::read_to_string(path).map_err(|e| error(e.to_string()))?;
+    parse(&content, path).map_err(|e| error(e.to_string()))
+}
+
+/// Follow a JSON pointer such as `/paths/~1pets/get` from `document`.
+fn resolve_pointer<'a>(document: &'a Value, pointer: &str) -> Option<&'a Value> {
+    if pointer.is_empty() {
+        return Some(document);
+    }
+    let mut value = document;
+    for token in pointer.strip_prefix('/')?.split('/') {
+        let token = unescape(token);
+        value = match value {
+            // YAML keys such as response codes may be numbers rather than strings
+            Value::Mapping(mapping) => mapping.iter().find_map(|(key, value)| {
+                let matches = match key {
+                    Value::String(key) => *key == token,
+                    Value::Number(key) => key.to_string() == token,
+                    Value::Bool(key) => key.to_string() == token,
+                    _ => false,
+                };
+                matches.then_some(value)
+            })?,
+            Value::Sequence(items) => items.get(token.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(value)
+}
+
+fn canonical(path: &Path) -> Result<PathBuf, ReferenceError> {
+    std::fs::canonicalize(path).map_err(|e| ReferenceError::File {
+        path: path.to_path_buf(),
+        message: e.to_string(),
+    })
+}
+
+fn reference_to(reference: &str) -> Value {
+    let mut mapping = Mapping::new();
+    mapping.insert("$ref".into(), reference.into());
+    Value::Mapping(mapping)
+}
+
+/// Undo JSON pointer escaping of a single token.
+fn unescape(token: &str) -> String {
+    token.replace("~1", "/").replace("~0", "~")
+}
+
+/// Undo URI percent encoding, which is allowed in the fragment of a reference.
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
@@ -0,0 +1,169 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result, bail};
+use reqwest::StatusCode;
+use reqwest::header::{CACHE_CONTROL, ETAG, HeaderMap, IF_NONE_MATCH};
+use serde::{Deserialize, Serialize};
+
+use crate::cache;
+
+/// How long a cached copy is used without asking the server, unless it sends a `max-age`.
+const DEFAULT_MAX_AGE: u64 = 60;
+
+/// How long to wait for the server when there's a cached copy to fall back on.
+const REVALIDATE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How long to wait for the server when there's nothing cached yet.
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What is known about the cached copy of a specification.
+#[derive(Default, Deserialize, Serialize)]
+struct Metadata {
+    /// The `ETag` the server sent with the copy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    etag: Option<String>,
+    /// When the server was last asked about the copy, in seconds since the Unix epoch
+    checked: u64,
+    /// Seconds the copy is fresh for after that, from the server's `Cache-Control: max-age`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_age: Option<u64>,
+}
+
+impl Metadata {
+    fn is_fresh(&self, now: u64) -> bool {
+        now.saturating_sub(self.checked) < self.max_age.unwrap_or(DEFAULT_MAX_AGE)
+    }
+}
+
+/// Whether a specification is given by URL rather than by path.
+pub(crate) fn is_url(spec: &str) -> bool {
+    spec.starts_with("http://") || spec.starts_with("https://")
+}
+
+/// Fetch the specification at `url` into the cache and return the path of the cached copy.
+///
+/// The cached copy is used without asking the server for the `max-age` the server sent, or a
+/// minute without one. After that it is revalidated with the `ETag` the server sent, so it is
+/// only downloaded again when the server says it changed. When the server can't be reached
+/// quickly or fails, the cached copy is used as it is and the server is asked again once the
+/// copy is no longer fresh.
+pub(crate) fn fetch(url: &str) -> Result<PathBuf> {
+    let directory = cache::directory()
+        .context("No cache directory, set XDG_CACHE_HOME or HOME")?
+        .join("specs");
+    std::fs::create_dir_all(&directory)?;
+    let name = format!("{:016x}", cache::hash(url.as_bytes()));
+    let spec_path = directory.join(format!("{}{}", name, extension(url)));
+    let metadata_path = directory.join(format!("{}.meta", name));
+    let metadata = std::fs::read(&metadata_path)
+        .ok()
+        .and_then(|content| serde_json::from_slice::<Metadata>(&content).ok());
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |now| now.as_secs());
+    let metadata = match metadata {
+        Some(metadata) if spec_path.exists() => metadata,
+        _ => Metadata::default(),
+    };
+    let is_cached = metadata.checked > 0;
+    if is_cached && metadata.is_fresh(now) {
+        return Ok(spec_path);
+    }
+
+    let client = reqwest::blocking::Client::builder()
+        .timeout(match is_cached {
+            true => REVALIDATE_TIMEOUT,
+            false => DOWNLOAD_TIMEOUT,
+        })
+        .build()?;
+    let mut request = client.get(url);
+    if let Some(etag) = &metadata.etag {
+        request = request.header(IF_NONE_MATCH, etag);
+    }
+    let response = match request.send() {
+        Ok(response) if response.status() == StatusCode::NOT_MODIFIED => {
+            let max_age = max_age(response.headers()).or(metadata.max_age);
+            let metadata = Metadata {
+                checked: now,
+                max_age,
+                ..metadata
+            };
+            store(&metadata_path, &metadata)?;
+            return Ok(spec_path);
+        }
+        Ok(response) if response.status().is_success() => response,
+        _ if is_cached => {
+            // The server isn't waited on again until the copy is stale
+            let metadata = Metadata {
+                checked: now,
+                ..metadata
+            };
+            store(&metadata_path, &metadata)?;
+            return Ok(spec_path);
+        }
+        Ok(response) => bail!("{} responded with {}", url, response.status()),
+        Err(e) => return Err(e.into()),
+    };
+    let metadata = Metadata {
+        etag: response
+            .headers()
+            .get(ETAG)
+            .and_then(|etag| etag.to_str().ok())
+            .map(str::to_string),
+        checked: now,
+        max_age: max_age(response.headers()),
+    };
+    write(&spec_path, response.text()?.as_bytes())?;
+    store(&metadata_path, &metadata)?;
+    Ok(spec_path)
+}
+
+fn store(metadata_path: &Path, metadata: &Metadata) -> Result<()> {
+    write(metadata_path, &serde_json::to_vec(metadata)?)
+}
+
+/// Replace the file at `path` in one step, so an interrupted or concurrent invocation never
+/// leaves half of it behind.
+fn write(path: &Path, content: &[u8]) -> Result<()> {
+    let mut temporary_path = path.as_os_str().to_owned();
+    temporary_path.push(format!(".{}.tmp", std::process::id()));
+    std::fs::write(&temporary_path, content)?;
+    std::fs::rename(&temporary_path, path)?;
+    Ok(())
+}
+
+/// The `max-age` of a response's `Cache-Control`, where `no-cache` and `no-store` count as 0.
+fn max_age(headers: &HeaderMap) -> Option<u64> {
+    let cache_control = headers.get(CACHE_CONTROL)?.to_str().ok()?;
+    cache_control
+        .split(',')
+        .map(str::trim)
+        .find_map(|directive| match directive.split_once('=') {
+            Some((name, seconds)) if name.eq_ignore_ascii_case("max-age") => {
+                seconds.trim_matches('"').parse().ok()
+            }
+            _ if directive.eq_ignore_ascii_case("no-cache")
+                || directive.eq_ignore_ascii_case("no-store") =>
+            {
+                Some(0)
+            }
+            _ => None,
+        })
+}
+
+/// The extension of the file at `url`, such as `.yaml`, so the cached copy is parsed the same
+/// way.
+fn extension(url: &str) -> String {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    let (_, location) = path.split_once("://").unwrap_or_default();
+    location
+        .split_once('/')
+        .and_then(|(_, path)| path.rsplit('/').next())
+        .and_then(|file| file.rsplit_once('.'))
+        .map(|(_, extension)| extension)
+        .filter(|extension| {
+            !extension.is_empty() && extension.chars().all(|c| c.is_ascii_alphanumeric())
+        })
+        .map_or_else(String::new, |extension| format!(".{}", extension))
+}
@@ -0,0 +1,231 @@
+use std::ops::Range;
+
+use crate::options;
+use crate::shell;
+
+/// An argument of a curl command that ding builds, by what it sets.
+#[derive(Debug, PartialEq)]
+enum Kind {
+    Method,
+    Get,
+    Url,
+    /// A header, by lowercase name
+    Header(String),
+    User,
+    Cookie(String),
+    /// A `-F` field
+    Form(String),
+    /// A `--data-urlencode` field, for the query or a form
+    Data(String),
+    Body,
+}
+
+struct Group {
+    kind: Kind,
+    value: String,
+    range: Range<usize>,
+}
+
+struct Edit {
+    range: Range<usize>,
+    text: String,
+    /// The group of the rendered command the text comes from
+    group: Option<usize>,
+}
+
+/// Rewrite `original` into `rendered`, changing only the arguments that differ.
+///
+/// Arguments ding doesn't build, such as `-s`, `-o file` or `--compressed`, and arguments with
+/// the same value in both are kept exactly as they were typed. Changed arguments are replaced
+/// where they were, removed ones are dropped and new ones are inserted after the argument that
+/// precedes them in `rendered`. `cursor` is an offset into `rendered`, which is moved to the
+/// same place in the result.
+pub(crate) fn rewrite(
+    original: &str,
+    rendered: &str,
+    cursor: Option<usize>,
+) -> (String, Option<usize>) {
+    let joined = shell::join_continuations(original);
+    let original_groups = groups(&joined);
+    let rendered_groups = groups(rendered);
+    let curl_end = options::split_words(&joined)
+        .first()
+        .map_or(0, |word| word.end);
+    let cursor_group = cursor.and_then(|cursor| {
+        rendered_groups
+            .iter()
+            .position(|group| group.range.start <= cursor && cursor <= group.range.end)
+    });
+
+    // Pair each rendered argument with the original one it replaces, preferring equal values
+    let mut matches: Vec<Option<usize>> = Vec::new();
+    for group in rendered_groups.iter() {
+        let is_free = |index: &usize| !matches.contains(&Some(*index));
+        let found = (0..original_groups.len())
+            .filter(is_free)
+            .find(|i| {
+                original_groups[*i].kind == group.kind
+                    && same_value(&group.kind, &original_groups[*i].value, &group.value)
+            })
+            .or_else(|| {
+                (0..original_groups.len())
+                    .filter(is_free)
+                    .find(|i| original_groups[*i].kind == group.kind)
+            });
+        matches.push(found);
+    }
+
+    let mut edits = Vec::new();
+    for (i, original_group) in original_groups.iter().enumerate() {
+        match matches.iter().position(|found| *found == Some(i)) {
+            Some(j) => {
+                let group = &rendered_groups[j];
+                // The argument under the cursor is rewritten unless the cursor is at its end
+                let is_under_cursor = cursor_group == Some(j)
+                    && !is_at_value_end(rendered, &group.range, cursor.unwrap_or_default());
+                let is_kept = same_value(&group.kind, &original_group.value, &group.value)
+                    && !is_under_cursor;
+                if !is_kept {
+                    edits.push(Edit {
+                        range: original_group.range.clone(),
+                        text: rendered[group.range.clone()].to_string(),
+                        group: Some(j),
+                    });
+                }
+            }
+            // A word ding took for a URL may be the value of an option it doesn't know
+            None if original_group.kind == Kind::Url => {}
+            None => {
+                let start = joined[..original_group.range.start]
+                    .trim_end_matches([' ', '\t'])
+                    .len();
+                edits.push(Edit {
+                    range: start..original_group.range.end,
+                    text: String::new(),
+                    group: None,
+                });
+            }
+        }
+    }
+    for (j, group) in rendered_groups.iter().enumerate() {
+        if matches[j].is_some() {
+            continue;
+        }
+        let anchor = (0..j)
+            .rev()
+            .find_map(|previous| matches[previous])
+            .map_or(curl_end, |i| original_groups[i].range.end);
+        edits.push(Edit {
+            range: anchor..anchor,
+            text: format!(" {}", &rendered[group.range.clone()]),
+            group: Some(j),
+        });
+    }
+    // Insertions go before an argument removed at the same place, in their rendered order
+    edits.sort_by_key(|edit| (edit.range.start, !edit.range.is_empty(), edit.group));
+
+    let mut command = String::with_capacity(rendered.len());
+    let mut group_starts = vec![None; rendered_groups.len()];
+    let mut last = 0;
+    for edit in edits.iter() {
+        command.push_str(&original[last..edit.range.start]);
+        if let Some(j) = edit.group {
+            let leading_space = edit.text.len() - edit.text.trim_start().len();
+            group_starts[j] = Some(command.len() + leading_space);
+        }
+        command.push_str(&edit.text);
+        last = edit.range.end;
+    }
+    command.push_str(&original[last..]);
+
+    let cursor = cursor.map(|cursor| {
+        let Some(j) = cursor_group else {
+            return cursor.min(command.len());
+        };
+        let group = &rendered_groups[j];
+        if let Some(start) = group_starts[j] {
+            return start + cursor - group.range.start;
+        }
+        // The original text was kept, so the cursor goes to the end of its value
+        let original_range = &original_groups[matches[j].unwrap_or_default()].range;
+        let offset: isize = edits
+            .iter()
+            .filter(|edit| edit.range.end <= original_range.start)
+            .map(|edit| edit.text.len() as isize - edit.range.len() as isize)
+            .sum();
+        let end = original_range.end.saturating_add_signed(offset);
+        match cursor < group.range.end && ends_with_quote(&original[original_range.clone()]) {
+            true => end - 1,
+            false => end,
+        }
+    });
+    (command, cursor)
+}
+
+/// The arguments of a command that ding builds.
+fn groups(command: &str) -> Vec<Group> {
+    options::arguments(command)
+        .into_iter()
+        .filter_map(|argument| {
+            let value = argument.value.unwrap_or_default();
+            let split = |separator: char| match value.split_once(separator) {
+                Some((name, value)) => (name.trim().to_string(), value.to_string()),
+                None => (value.trim().to_string(), String::new()),
+            };
+            let (kind, value) = match argument.flag.as_deref() {
+                None | Some("--url") => (Kind::Url, value),
+                Some("-X" | "--request") => (Kind::Method, value),
+                Some("-G" | "--get") => (Kind::Get, value),
+                Some("-H" | "--header") => {
+                    let (name, value) = value.split_once(':')?;
+                    (
+                        Kind::Header(name.trim().to_lowercase()),
+                        value.trim().to_string(),
+                    )
+                }
+                Some("-u" | "--user") => (Kind::User, value),
+                Some("-b" | "--cookie") => {
+                    let (name, value) = split('=');
+                    (Kind::Cookie(name), value)
+                }
+                Some("-F" | "--form") => {
+                    let (name, value) = split('=');
+                    (Kind::Form(name), value)
+                }
+                Some("--data-urlencode") => {
+                    let (name, value) = split('=');
+                    (Kind::Data(name), value)
+                }
+                Some("-d" | "--data" | "--data-raw" | "--data-binary" | "--data-ascii") => {
+                    (Kind::Body, value)
+                }
+                Some(_) => return None,
+            };
+            Some(Group {
+                kind,
+                value,
+                range: argument.range,
+            })
+        })
+        .collect()
+}
+
+/// Whether two values are the same, ignoring the formatting of JSON bodies.
+fn same_value(kind: &Kind, original: &str, rendered: &str) -> bool {
+    if *kind == Kind::Body
+        && let Ok(original) = serde_json::from_str::<serde_json::Value>(original)
+        && let Ok(rendered) = serde_json::from_str::<serde_json::Value>(rendered)
+    {
+        return original == rendered;
+    }
+    original == rendered
+}
+
+/// Whether `cursor` is at the end of the argument's value, before any closing quote.
+fn is_at_value_end(command: &str, range: &Range<usize>, cursor: usize) -> bool {
+    cursor == range.end || (cursor + 1 == range.end && ends_with_quote(&command[range.clone()]))
+}
+
+fn ends_with_quote(text: &str) -> bool {
+    text.ends_with(['\'', '"'])
+}
@@ -0,0 +1,83 @@
+use anyhow::Result;
+use openapiv3::{APIKeyLocation, OpenAPI, Operation, SecurityScheme};
+
+use crate::ReferenceOrExt;
+use crate::config::Config;
+
+/// A credential the operation needs, with its secret or a placeholder for it.
+#[derive(Debug)]
+pub(crate) enum Credential {
+    Header {
+        name: String,
+        value: String,
+    },
+    Query {
+        name: String,
+        value: String,
+    },
+    Cookie {
+        name: String,
+        value: String,
+    },
+    /// `-u` credentials for basic auth
+    User(String),
+}
+
+/// The credentials for the operation's first security requirement.
+///
+/// The operation's `security` overrides the specification's, and an empty list means the
+/// operation is public. Secrets come from the environment variables named in the configuration.
+pub(crate) fn credentials(
+    operation: &Operation,
+    spec: &OpenAPI,
+    config: &Config,
+) -> Result<Vec<Credential>> {
+    let requirements = operation.security.as_ref().or(spec.security.as_ref());
+    let Some(requirement) = requirements.and_then(|requirements| requirements.first()) else {
+        return Ok(vec![]);
+    };
+    let mut credentials = Vec::new();
+    for scheme_name in requirement.keys() {
+        let scheme = spec
+            .components
+            .as_ref()
+            .and_then(|components| components.security_schemes.get(scheme_name))
+            .ok_or_else(|| anyhow::anyhow!("security scheme {} does not exist", scheme_name))?
+            .item(&spec.components)?;
+        let secret = config.secret(scheme_name);
+        credentials.push(credential(scheme, secret));
+    }
+    Ok(credentials)
+}
+
+fn credential(scheme: &SecurityScheme, secret: Option<String>) -> Credential {
+    match scheme {
+        SecurityScheme::APIKey { location, name, .. } => {
+            let name = name.to_string();
+            let value = secret.unwrap_or_else(|| "<api-key>".to_string());
+            match location {
+                APIKeyLocation::Header => Credential::Header { name, value },
+                APIKeyLocation::Query => Credential::Query { name, value },
+                APIKeyLocation::Cookie => Credential::Cookie { name, value },
+            }
+        }
+        SecurityScheme::HTTP { scheme, .. } if scheme.eq_ignore_ascii_case("basic") => {
+            Credential::User(secret.unwrap_or_else(|| "<username>:<password>".to_string()))
+        }
+        SecurityScheme::HTTP { scheme, .. } if !scheme.eq_ignore_ascii_case("bearer") => {
+            Credential::Header {
+                name: "Authorization".to_string(),
+                value: format!(
+                    "{} {}",
+                    scheme,
+                    secret.unwrap_or_else(|| "<credentials>".to_string())
+                ),
+            }
+        }
+        // Bearer tokens, and the access tokens OAuth 2 and OpenID Connect hand out
+        _ => Credential::Header {
+            name: "Authorization".to_string(),
+            value: format!("Bearer {}", secret.unwrap_or_else(|| "<token>".to_string())),
+        },
+    }
+}
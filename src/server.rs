@@ -0,0 +1,131 @@
+use indexmap::IndexMap;
+use openapiv3::{OpenAPI, Server};
+
+use crate::outline::Outline;
+use crate::url_path_start;
+
+/// A server's URL with each variable replaced by its default and no trailing slash.
+pub(crate) fn server_url(server: &Server) -> String {
+    let mut url = server.url.to_string();
+    for (name, variable) in server.variables.iter().flatten() {
+        let value = match variable.default.is_empty() {
+            true => variable.enumeration.first().cloned().unwrap_or_default(),
+            false => variable.default.to_string(),
+        };
+        url = url.replace(&format!("{{{}}}", name), &value);
+    }
+    url.trim_end_matches('/').to_string()
+}
+
+/// The path part of a server URL, such as `/v1` for `https://example.com/v1`.
+pub(crate) fn base_path(url: &str) -> &str {
+    url[url_path_start(url)..].trim_end_matches('/')
+}
+
+/// A path, with its query, sent to a server.
+///
+/// The server's base path isn't repeated when the path already starts with it.
+pub(crate) fn url_on_server(server: &Server, path_and_query: &str) -> String {
+    let server_url = server_url(server);
+    let base_path = base_path(&server_url);
+    match path_and_query.starts_with(&format!("{}/", base_path)) {
+        true => format!(
+            "{}{}",
+            &server_url[..server_url.len() - base_path.len()],
+            path_and_query
+        ),
+        false => format!("{}{}", server_url, path_and_query),
+    }
+}
+
+/// The longest base path of any server in the specification that `path` is under.
+///
+/// Servers can be declared on the specification, its paths and its operations, so every one
+/// of them is considered.
+pub(crate) fn matching_base_path(
+    spec: &OpenAPI,
+    outlines: &IndexMap<String, Outline>,
+    path: &str,
+) -> String {
+    all_servers(spec, outlines)
+        .map(server_url)
+        .map(|url| base_path(&url).to_string())
+        .filter(|base| !base.is_empty() && path.starts_with(&format!("{}/", base)))
+        .max_by_key(|base| base.len())
+        .unwrap_or_default()
+}
+
+/// The servers a request is sent to: the operation's own, else its path's, else the
+/// specification's.
+pub(crate) fn applicable_servers<'a>(
+    spec: &'a OpenAPI,
+    outlines: &'a IndexMap<String, Outline>,
+    path: &str,
+    method: &str,
+) -> &'a [Server] {
+    let base_path = matching_base_path(spec, outlines, path);
+    let path = &path[base_path.len()..];
+    let outline = outlines
+        .iter()
+        .find(|(template, _)| matches_template(path, template))
+        .map(|(_, outline)| outline);
+    if let Some(outline) = outline {
+        let operation_servers = outline
+            .operation_servers
+            .iter()
+            .find(|(operation_method, _)| operation_method.eq_ignore_ascii_case(method));
+        if let Some((_, servers)) = operation_servers {
+            return servers;
+        }
+        if !outline.servers.is_empty() {
+            return &outline.servers;
+        }
+    }
+    &spec.servers
+}
+
+/// Pick a server by its index or by a case-insensitive match on its description.
+///
+/// Without a selection the first server is used.
+pub(crate) fn select<'a>(servers: &'a [Server], selection: Option<&str>) -> Option<&'a Server> {
+    let Some(selection) = selection else {
+        return servers.first();
+    };
+    if let Ok(index) = selection.parse::<usize>() {
+        return servers.get(index);
+    }
+    let selection = selection.to_lowercase();
+    servers.iter().find(|server| {
+        server
+            .description
+            .as_deref()
+            .is_some_and(|description| description.to_lowercase().contains(&selection))
+    })
+}
+
+fn all_servers<'a>(
+    spec: &'a OpenAPI,
+    outlines: &'a IndexMap<String, Outline>,
+) -> impl Iterator<Item = &'a Server> {
+    spec.servers
+        .iter()
+        .chain(outlines.values().flat_map(|outline| {
+            outline
+                .servers
+                .iter()
+                .chain(outline.operation_servers.values().flatten())
+        }))
+}
+
+fn matches_template(path: &str, template: &str) -> bool {
+    let segments = path.split('/').collect::<Vec<_>>();
+    let template_segments = template.split('/').collect::<Vec<_>>();
+    segments.len() == template_segments.len()
+        && segments
+            .iter()
+            .zip(template_segments)
+            .all(|(segment, template_segment)| {
+                segment == &template_segment
+                    || (template_segment.starts_with('{') && template_segment.ends_with('}'))
+            })
+}
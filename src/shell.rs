@@ -0,0 +1,201 @@
+use std::ops::Range;
+
+/// Words that can come before the command name without being the command itself.
+const PREFIX_WORDS: [&str; 11] = [
+    "{", "!", "if", "then", "elif", "else", "while", "until", "do", "time", "exec",
+];
+
+/// Byte range of the curl command under the cursor in a shell buffer, or of the first one.
+///
+/// Commands are separated by pipes, `&&`, `||`, `;`, `&` and newlines, and may be nested in
+/// `$(...)`, backticks or subshells. Separators inside quotes don't count, and a backslash at the
+/// end of a line continues the command onto the next one.
+pub(crate) fn find_curl_command(buffer: &str, cursor: Option<usize>) -> Option<Range<usize>> {
+    let mut commands = commands(buffer)
+        .into_iter()
+        .filter_map(|command| curl_command(buffer, command))
+        .collect::<Vec<_>>();
+    commands.sort_by_key(|command| command.start);
+    let under_cursor = cursor.and_then(|cursor| {
+        commands
+            .iter()
+            .filter(|command| command.start <= cursor && cursor <= command.end)
+            // Commands nested in `$(...)` start later than the ones around them
+            .max_by_key(|command| command.start)
+            .cloned()
+    });
+    under_cursor.or_else(|| commands.into_iter().next())
+}
+
+/// A curl command as the curl parser expects it, on a single line.
+///
+/// Continuations are replaced by spaces of the same length, so offsets into the command stay
+/// the same. A backslash and newline inside single quotes are literal, so they're kept.
+pub(crate) fn join_continuations(command: &str) -> String {
+    let mut joined = String::with_capacity(command.len());
+    let mut chars = command.chars().peekable();
+    let mut quote = None;
+    while let Some(c) = chars.next() {
+        match (c, quote) {
+            ('\\', Some('\'')) => joined.push(c),
+            ('\\', _) if chars.peek() == Some(&'\n') => {
+                chars.next();
+                joined.push_str("  ");
+            }
+            ('\\', _) if chars.peek() == Some(&'\r') => {
+                let mut rest = chars.clone();
+                rest.next();
+                if rest.next() == Some('\n') {
+                    chars = rest;
+                    joined.push_str("   ");
+                } else {
+                    joined.push(c);
+                }
+            }
+            ('\\', _) => {
+                // Escaped characters, such as quotes, don't open or close anything
+                joined.push(c);
+                if let Some(escaped) = chars.next() {
+                    joined.push(escaped);
+                }
+            }
+            ('\'' | '"', None) => {
+                quote = Some(c);
+                joined.push(c);
+            }
+            (c, Some(open)) if c == open => {
+                quote = None;
+                joined.push(c);
+            }
+            (c, _) => joined.push(c),
+        }
+    }
+    joined
+}
+
+/// The part of a simple command starting at `curl`, when that's the command being run.
+fn curl_command(buffer: &str, command: Range<usize>) -> Option<Range<usize>> {
+    let mut offset = command.start;
+    loop {
+        let rest = &buffer[offset..command.end];
+        let word_end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let word = &rest[..word_end];
+        let is_assignment = word
+            .split_once('=')
+            .is_some_and(|(name, _)| !name.is_empty() && !name.starts_with('-'));
+        if word == "curl" {
+            return Some(offset..command.end);
+        }
+        if word_end == rest.len() || !(PREFIX_WORDS.contains(&word) || is_assignment) {
+            return None;
+        }
+        offset += word_end + rest[word_end..].len() - rest[word_end..].trim_start().len();
+    }
+}
+
+#[derive(PartialEq)]
+enum Closer {
+    /// The end of the buffer
+    End,
+    /// `)` closing `$(` or a subshell
+    Parenthesis,
+    Backtick,
+}
+
+struct Frame {
+    /// Where the current command in this frame started
+    start: usize,
+    closer: Closer,
+    quote: Option<u8>,
+}
+
+/// The byte ranges of every simple command in the buffer, trimmed of whitespace.
+fn commands(buffer: &str) -> Vec<Range<usize>> {
+    let bytes = buffer.as_bytes();
+    let mut commands = Vec::new();
+    let mut push = |start: usize, end: usize| {
+        let text = &buffer[start..end];
+        let trimmed = text.trim_start();
+        let start = start + text.len() - trimmed.len();
+        let mut end = start + trimmed.trim_end().len();
+        // A continuation before a separator on the next line belongs to neither command
+        while end > start
+            && bytes[end - 1] == b'\\'
+            && (bytes[end..].starts_with(b"\n") || bytes[end..].starts_with(b"\r\n"))
+        {
+            end = start + buffer[start..end - 1].trim_end().len();
+        }
+        if start < end {
+            commands.push(start..end);
+        }
+    };
+    let mut frames = vec![Frame {
+        start: 0,
+        closer: Closer::End,
+        quote: None,
+    }];
+    let mut escaped = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        let next = bytes.get(i + 1).copied();
+        let previous = i.checked_sub(1).map(|previous| bytes[previous]);
+        let frame = frames.last_mut().unwrap();
+        if escaped {
+            escaped = false;
+            i += 1;
+            continue;
+        }
+        match (frame.quote, c) {
+            (Some(b'\''), b'\'') => frame.quote = None,
+            (Some(b'\''), _) => {}
+            (_, b'\\') => escaped = true,
+            (Some(b'"'), b'"') => frame.quote = None,
+            (_, b'$') if next == Some(b'(') => {
+                i += 1;
+                frames.push(Frame {
+                    start: i + 1,
+                    closer: Closer::Parenthesis,
+                    quote: None,
+                });
+            }
+            (_, b'`') if frame.closer == Closer::Backtick && frame.quote.is_none() => {
+                push(frame.start, i);
+                frames.pop();
+            }
+            (_, b'`') => frames.push(Frame {
+                start: i + 1,
+                closer: Closer::Backtick,
+                quote: None,
+            }),
+            (Some(_), _) => {}
+            (None, b'\'' | b'"') => frame.quote = Some(c),
+            (None, b'(') => frames.push(Frame {
+                start: i + 1,
+                closer: Closer::Parenthesis,
+                quote: None,
+            }),
+            (None, b')') if frame.closer == Closer::Parenthesis => {
+                push(frame.start, i);
+                frames.pop();
+            }
+            // `2>&1` and `&>` are redirections rather than separators
+            (None, b'&') if matches!(previous, Some(b'>' | b'<')) || next == Some(b'>') => {}
+            (None, b'|' | b'&' | b';' | b'\n') => {
+                push(frame.start, i);
+                let is_double = next == Some(c) || (c == b'|' && next == Some(b'&'));
+                if is_double && c != b'\n' {
+                    i += 1;
+                }
+                frame.start = i + 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    // Unclosed frames run to the end of the buffer, as they would while still being typed
+    for frame in frames.iter().rev() {
+        push(frame.start, bytes.len());
+    }
+    commands
+}
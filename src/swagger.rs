@@ -0,0 +1,515 @@
+use serde_yaml::{Mapping, Value};
+
+const OPERATIONS: [&str; 7] = ["get", "put", "post", "delete", "options", "head", "patch"];
+
+/// Keys that describe the value of a Swagger 2.0 parameter or header and move into its schema.
+const SCHEMA_KEYS: [&str; 16] = [
+    "type",
+    "format",
+    "items",
+    "default",
+    "maximum",
+    "exclusiveMaximum",
+    "minimum",
+    "exclusiveMinimum",
+    "maxLength",
+    "minLength",
+    "pattern",
+    "maxItems",
+    "minItems",
+    "uniqueItems",
+    "enum",
+    "multipleOf",
+];
+
+const URL_ENCODED: &str = "application/x-www-form-urlencoded";
+const MULTIPART: &str = "multipart/form-data";
+
+/// Whether the document is a Swagger 2.0 specification.
+pub(crate) fn is_swagger(spec: &Value) -> bool {
+    match spec.get("swagger") {
+        Some(Value::String(version)) => version.starts_with("2."),
+        // `swagger: 2.0` without quotes is a number
+        Some(Value::Number(version)) => version.as_f64() == Some(2.0),
+        _ => false,
+    }
+}
+
+/// Convert a Swagger 2.0 specification into the OpenAPI 3.0 document it describes.
+///
+/// The specification is expected to be bundled already, so the only references left point at
+/// `components/schemas`. `host`, `basePath` and `schemes` become `servers`, `body` and `formData`
+/// parameters become the request body of every type the operation `consumes`, and responses
+/// get content for every type it `produces`.
+pub(crate) fn convert(spec: Value) -> Value {
+    let Value::Mapping(mut spec) = spec else {
+        return spec;
+    };
+    let consumes = media_types(spec.get("consumes"));
+    let produces = media_types(spec.get("produces"));
+
+    let mut converted = Mapping::new();
+    converted.insert("openapi".into(), "3.0.3".into());
+    for (key, value) in spec.iter() {
+        let is_kept = matches!(
+            key.as_str(),
+            Some("info" | "tags" | "externalDocs" | "security")
+        ) || key.as_str().is_some_and(|key| key.starts_with("x-"));
+        if is_kept {
+            converted.insert(key.clone(), value.clone());
+        }
+    }
+    let servers = servers(&spec);
+    if !servers.is_empty() {
+        converted.insert("servers".into(), Value::Sequence(servers));
+    }
+
+    let mut paths = Mapping::new();
+    if let Some(Value::Mapping(items)) = spec.remove("paths") {
+        for (template, item) in items {
+            let item = match item {
+                Value::Mapping(item) => path_item(item, &consumes, &produces),
+                item => item,
+            };
+            paths.insert(template, item);
+        }
+    }
+    converted.insert("paths".into(), Value::Mapping(paths));
+
+    // Schemas hoisted while bundling are already in components
+    let mut components = match spec.remove("components") {
+        Some(Value::Mapping(components)) => components,
+        _ => Mapping::new(),
+    };
+    let mut schemas = match components.remove("schemas") {
+        Some(Value::Mapping(schemas)) => schemas,
+        _ => Mapping::new(),
+    };
+    if let Some(Value::Mapping(definitions)) = spec.remove("definitions") {
+        schemas.extend(definitions);
+    }
+    if !schemas.is_empty() {
+        let schemas = schemas
+            .into_iter()
+            .map(|(name, definition)| (name, schema(definition)))
+            .collect();
+        components.insert("schemas".into(), Value::Mapping(schemas));
+    }
+    if let Some(Value::Mapping(definitions)) = spec.remove("securityDefinitions") {
+        let schemes = definitions
+            .into_iter()
+            .map(|(name, definition)| (name, security_scheme(definition)))
+            .collect();
+        components.insert("securitySchemes".into(), Value::Mapping(schemes));
+    }
+    if !components.is_empty() {
+        converted.insert("components".into(), Value::Mapping(components));
+    }
+    Value::Mapping(converted)
+}
+
+/// A server for every scheme the API is served over.
+fn servers(spec: &Mapping) -> Vec<Value> {
+    let host = spec.get("host").and_then(Value::as_str);
+    let base_path = spec
+        .get("basePath")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .trim_end_matches('/');
+    let Some(host) = host else {
+        if base_path.is_empty() {
+            return vec![];
+        }
+        return vec![server(base_path.to_string())];
+    };
+    let schemes = match spec.get("schemes") {
+        Some(Value::Sequence(schemes)) if !schemes.is_empty() => {
+            schemes.iter().filter_map(Value::as_str).collect()
+        }
+        _ => vec!["https"],
+    };
+    schemes
+        .into_iter()
+        .map(|scheme| server(format!("{}://{}{}", scheme, host, base_path)))
+        .collect()
+}
+
+fn server(url: String) -> Value {
+    let mut server = Mapping::new();
+    server.insert("url".into(), url.into());
+    Value::Mapping(server)
+}
+
+fn path_item(mut item: Mapping, consumes: &[String], produces: &[String]) -> Value {
+    // Body and form parameters shared by the path are merged into every operation's own
+    let (shared_parameters, shared_body) = split_parameters(item.remove("parameters"));
+    let mut converted = Mapping::new();
+    for (key, value) in item {
+        let operation = match (key.as_str(), value) {
+            (Some(method), Value::Mapping(operation)) if OPERATIONS.contains(&method) => {
+                self::operation(operation, &shared_body, consumes, produces)
+            }
+            (_, value) => value,
+        };
+        converted.insert(key, operation);
+    }
+    if !shared_parameters.is_empty() {
+        converted.insert("parameters".into(), Value::Sequence(shared_parameters));
+    }
+    Value::Mapping(converted)
+}
+
+fn operation(
+    mut operation: Mapping,
+    shared_body: &[Mapping],
+    consumes: &[String],
+    produces: &[String],
+) -> Value {
+    let consumes = match operation.remove("consumes") {
+        Some(types) => media_types(Some(&types)),
+        None => consumes.to_vec(),
+    };
+    let produces = match operation.remove("produces") {
+        Some(types) => media_types(Some(&types)),
+        None => produces.to_vec(),
+    };
+    operation.remove("schemes");
+
+    let (parameters, own_body) = split_parameters(operation.remove("parameters"));
+    // The operation's parameters override the path's with the same name and location, and
+    // there's only one `body`
+    let body = shared_body
+        .iter()
+        .filter(|shared| {
+            !own_body.iter().any(|own| {
+                let location = own.get("in");
+                location == shared.get("in")
+                    && (location.and_then(Value::as_str) == Some("body")
+                        || own.get("name") == shared.get("name"))
+            })
+        })
+        .chain(&own_body)
+        .cloned()
+        .collect::<Vec<_>>();
+    let mut converted = Mapping::new();
+    for (key, value) in operation {
+        let value = match (key.as_str(), value) {
+            (Some("responses"), Value::Mapping(responses)) => {
+                let responses = responses
+                    .into_iter()
+                    .map(|(status, response)| (status, self::response(response, &produces)))
+                    .collect();
+                Value::Mapping(responses)
+            }
+            (_, value) => value,
+        };
+        converted.insert(key, value);
+    }
+    if !parameters.is_empty() {
+        converted.insert("parameters".into(), Value::Sequence(parameters));
+    }
+    if let Some(request_body) = request_body(&body, &consumes) {
+        converted.insert("requestBody".into(), request_body);
+    }
+    if !converted.contains_key("responses") {
+        converted.insert("responses".into(), Value::Mapping(Mapping::new()));
+    }
+    Value::Mapping(converted)
+}
+
+/// Convert query, header and path parameters, and set aside `body` and `formData` ones.
+fn split_parameters(parameters: Option<Value>) -> (Vec<Value>, Vec<Mapping>) {
+    let Some(Value::Sequence(parameters)) = parameters else {
+        return (vec![], vec![]);
+    };
+    let mut converted = vec![];
+    let mut body = vec![];
+    for parameter in parameters {
+        match parameter {
+            Value::Mapping(parameter)
+                if matches!(
+                    parameter.get("in").and_then(Value::as_str),
+                    Some("body" | "formData")
+                ) =>
+            {
+                body.push(parameter)
+            }
+            Value::Mapping(parameter) => converted.push(self::parameter(parameter)),
+            parameter => converted.push(parameter),
+        }
+    }
+    (converted, body)
+}
+
+fn parameter(mut parameter: Mapping) -> Value {
+    let location = parameter
+        .get("in")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    let collection_format = parameter.remove("collectionFormat");
+    let is_array = parameter.get("type").and_then(Value::as_str) == Some("array");
+    let example = parameter.remove("x-example");
+    let mut schema = take_schema(&mut parameter);
+    if is_array {
+        // Arrays are comma separated in a single value unless the parameter says otherwise
+        let (style, explode) = match collection_format.as_ref().and_then(Value::as_str) {
+            Some("multi") => ("form", true),
+            Some("ssv") => ("spaceDelimited", false),
+            Some("pipes") => ("pipeDelimited", false),
+            _ if location == "query" => ("form", false),
+            _ => ("simple", false),
+        };
+        parameter.insert("style".into(), style.into());
+        parameter.insert("explode".into(), explode.into());
+    }
+    if let Some(example) = example {
+        parameter.insert("example".into(), example);
+    }
+    if let Value::Mapping(schema) = &mut schema {
+        schema.remove("collectionFormat");
+    }
+    parameter.insert("schema".into(), schema);
+    Value::Mapping(parameter)
+}
+
+/// Move the keys describing a parameter or header value into a schema of their own.
+fn take_schema(mapping: &mut Mapping) -> Value {
+    let mut schema = Mapping::new();
+    for key in SCHEMA_KEYS {
+        if let Some(value) = mapping.remove(key) {
+            schema.insert(key.into(), value);
+        }
+    }
+    self::schema(Value::Mapping(schema))
+}
+
+/// The request body described by `body` or `formData` parameters.
+fn request_body(parameters: &[Mapping], consumes: &[String]) -> Option<Value> {
+    let mut request_body = Mapping::new();
+    let body = parameters
+        .iter()
+        .find(|parameter| parameter.get("in").and_then(Value::as_str) == Some("body"));
+    let (schema, media_types) = match body {
+        Some(body) => {
+            for key in ["description", "required"] {
+                if let Some(value) = body.get(key) {
+                    request_body.insert(key.into(), value.clone());
+                }
+            }
+            let schema = body.get("schema").cloned().map(schema).unwrap_or_default();
+            (schema, consumes.to_vec())
+        }
+        None if parameters.is_empty() => return None,
+        None => form(parameters, consumes),
+    };
+    let content = media_types
+        .into_iter()
+        .map(|media_type| {
+            let mut content = Mapping::new();
+            content.insert("schema".into(), schema.clone());
+            (media_type.into(), Value::Mapping(content))
+        })
+        .collect();
+    request_body.insert("content".into(), Value::Mapping(content));
+    Some(Value::Mapping(request_body))
+}
+
+/// The object schema of `formData` parameters and the form types it can be sent as.
+fn form(parameters: &[Mapping], consumes: &[String]) -> (Value, Vec<String>) {
+    let mut properties = Mapping::new();
+    let mut required = vec![];
+    let mut has_file = false;
+    for parameter in parameters {
+        let Some(name) = parameter.get("name").cloned() else {
+            continue;
+        };
+        let mut parameter = parameter.clone();
+        has_file |= parameter.get("type").and_then(Value::as_str) == Some("file");
+        let mut property = take_schema(&mut parameter);
+        if let (Value::Mapping(property), Some(description)) =
+            (&mut property, parameter.get("description"))
+        {
+            property.insert("description".into(), description.clone());
+        }
+        if parameter.get("required").and_then(Value::as_bool) == Some(true) {
+            required.push(name.clone());
+        }
+        properties.insert(name, property);
+    }
+    let mut schema = Mapping::new();
+    schema.insert("type".into(), "object".into());
+    schema.insert("properties".into(), Value::Mapping(properties));
+    if !required.is_empty() {
+        schema.insert("required".into(), Value::Sequence(required));
+    }
+
+    let mut media_types: Vec<String> = consumes
+        .iter()
+        .filter(|media_type| {
+            media_type.starts_with(URL_ENCODED) || media_type.starts_with(MULTIPART)
+        })
+        .cloned()
+        .collect();
+    if media_types.is_empty() {
+        // Files can only be uploaded in a multipart form
+        let media_type = if has_file { MULTIPART } else { URL_ENCODED };
+        media_types.push(media_type.to_string());
+    }
+    (Value::Mapping(schema), media_types)
+}
+
+fn response(response: Value, produces: &[String]) -> Value {
+    let Value::Mapping(mut response) = response else {
+        return response;
+    };
+    let mut converted = Mapping::new();
+    converted.insert(
+        "description".into(),
+        response.remove("description").unwrap_or("".into()),
+    );
+    if let Some(Value::Mapping(headers)) = response.remove("headers") {
+        let headers = headers
+            .into_iter()
+            .map(|(name, header)| {
+                let header = match header {
+                    Value::Mapping(mut header) => {
+                        let schema = take_schema(&mut header);
+                        let mut converted = Mapping::new();
+                        if let Some(description) = header.remove("description") {
+                            converted.insert("description".into(), description);
+                        }
+                        converted.insert("schema".into(), schema);
+                        Value::Mapping(converted)
+                    }
+                    header => header,
+                };
+                (name, header)
+            })
+            .collect();
+        converted.insert("headers".into(), Value::Mapping(headers));
+    }
+    if let Some(schema) = response.remove("schema") {
+        let schema = self::schema(schema);
+        let examples = response.remove("examples");
+        let content = produces
+            .iter()
+            .map(|media_type| {
+                let mut content = Mapping::new();
+                content.insert("schema".into(), schema.clone());
+                if let Some(example) = examples
+                    .as_ref()
+                    .and_then(|examples| examples.get(media_type.as_str()))
+                {
+                    content.insert("example".into(), example.clone());
+                }
+                (media_type.as_str().into(), Value::Mapping(content))
+            })
+            .collect();
+        converted.insert("content".into(), Value::Mapping(content));
+    }
+    Value::Mapping(converted)
+}
+
+/// Convert the few schema keywords that differ between Swagger 2.0 and OpenAPI 3.0.
+fn schema(schema: Value) -> Value {
+    match schema {
+        Value::Mapping(schema) => {
+            let mut converted = Mapping::new();
+            for (key, value) in schema {
+                match (key.as_str(), value) {
+                    (Some("x-nullable"), value) => {
+                        converted.insert("nullable".into(), value);
+                    }
+                    (Some("discriminator"), Value::String(property_name)) => {
+                        let mut discriminator = Mapping::new();
+                        discriminator.insert("propertyName".into(), property_name.into());
+                        converted.insert(key, Value::Mapping(discriminator));
+                    }
+                    (Some("type"), Value::String(kind)) if kind == "file" => {
+                        converted.insert(key, "string".into());
+                        converted.insert("format".into(), "binary".into());
+                    }
+                    (Some("properties"), Value::Mapping(properties)) => {
+                        let properties = properties
+                            .into_iter()
+                            .map(|(name, property)| (name, self::schema(property)))
+                            .collect();
+                        converted.insert(key, Value::Mapping(properties));
+                    }
+                    (Some("allOf"), Value::Sequence(schemas)) => {
+                        let schemas = schemas.into_iter().map(self::schema).collect();
+                        converted.insert(key, Value::Sequence(schemas));
+                    }
+                    (Some("items" | "additionalProperties"), value) => {
+                        converted.insert(key, self::schema(value));
+                    }
+                    (_, value) => {
+                        converted.insert(key, value);
+                    }
+                }
+            }
+            Value::Mapping(converted)
+        }
+        schema => schema,
+    }
+}
+
+fn security_scheme(definition: Value) -> Value {
+    let Value::Mapping(mut definition) = definition else {
+        return definition;
+    };
+    let mut converted = Mapping::new();
+    if let Some(description) = definition.remove("description") {
+        converted.insert("description".into(), description);
+    }
+    match definition.get("type").and_then(Value::as_str) {
+        Some("basic") => {
+            converted.insert("type".into(), "http".into());
+            converted.insert("scheme".into(), "basic".into());
+        }
+        Some("oauth2") => {
+            let flow = match definition.get("flow").and_then(Value::as_str) {
+                Some("password") => "password",
+                Some("application") => "clientCredentials",
+                Some("accessCode") => "authorizationCode",
+                _ => "implicit",
+            };
+            let mut settings = Mapping::new();
+            for key in ["authorizationUrl", "tokenUrl"] {
+                if let Some(url) = definition.remove(key) {
+                    settings.insert(key.into(), url);
+                }
+            }
+            settings.insert(
+                "scopes".into(),
+                definition
+                    .remove("scopes")
+                    .unwrap_or_else(|| Mapping::new().into()),
+            );
+            let mut flows = Mapping::new();
+            flows.insert(flow.into(), Value::Mapping(settings));
+            converted.insert("type".into(), "oauth2".into());
+            converted.insert("flows".into(), Value::Mapping(flows));
+        }
+        // apiKey is the same in both versions
+        _ => converted.extend(definition),
+    }
+    Value::Mapping(converted)
+}
+
+/// The media types listed in `consumes` or `produces`, JSON when there are none.
+fn media_types(types: Option<&Value>) -> Vec<String> {
+    let types: Vec<String> = match types {
+        Some(Value::Sequence(types)) => types
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        _ => vec![],
+    };
+    if types.is_empty() {
+        return vec!["application/json".to_string()];
+    }
+    types
+}
@@ -3,6 +3,18 @@ mod tests {
 
     use insta_cmd::{Command, assert_cmd_snapshot, get_cargo_bin};
     use std::io::Write;
+    use std::path::Path;
+    use std::str::FromStr;
+
+    /// The ding binary, without the configuration or the cache of whoever runs the tests.
+    fn ding() -> Command {
+        let mut cmd = Command::new(get_cargo_bin("ding"));
+        cmd.env("DING_NO_IMPLICIT_CONFIG", "1").env(
+            "XDG_CACHE_HOME",
+            Path::new(env!("CARGO_TARGET_TMPDIR")).join("cache"),
+        );
+        cmd
+    }
 
     #[test]
     fn missing_spec() {
@@ -12,7 +24,7 @@ mod tests {
 
     #[test]
     fn complete_query_parameter() {
-        let mut cmd = Command::new(get_cargo_bin("ding"));
+        let mut cmd = ding();
         let cmd = cmd
             .arg("--spec")
             .arg("tests/petstore.yaml")
@@ -37,7 +49,7 @@ mod tests {
 
     #[test]
     fn alternates_query_parameters() {
-        let mut cmd = Command::new(get_cargo_bin("ding"));
+        let mut cmd = ding();
         let cmd = cmd
             .arg("--spec")
             .arg("tests/petstore.yaml")
@@ -78,7 +90,7 @@ mod tests {
 
     #[test]
     fn complete_header_parameter() {
-        let mut cmd = Command::new(get_cargo_bin("ding"));
+        let mut cmd = ding();
         let cmd = cmd
             .arg("--spec")
             .arg("tests/petstore.yaml")
@@ -103,7 +115,7 @@ mod tests {
 
     #[test]
     fn alternates_header_parameters() {
-        let mut cmd = Command::new(get_cargo_bin("ding"));
+        let mut cmd = ding();
         let cmd = cmd
             .arg("--spec")
             .arg("tests/petstore.yaml")
@@ -144,7 +156,7 @@ mod tests {
 
     #[test]
     fn complete_request_body_parameter() {
-        let mut cmd = Command::new(get_cargo_bin("ding"));
+        let mut cmd = ding();
         let cmd = cmd
             .arg("--spec")
             .arg("tests/petstore.yaml")
@@ -167,9 +179,34 @@ mod tests {
         insta::assert_snapshot!(output_str);
     }
 
+    #[test]
+    fn complete_request_body_from_schema() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X POST https://localhost:9000/stores")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
     #[test]
     fn complete_command_with_pipe_end() {
-        let mut cmd = Command::new(get_cargo_bin("ding"));
+        let mut cmd = ding();
         let cmd = cmd
             .arg("--spec")
             .arg("tests/petstore.yaml")
@@ -194,7 +231,7 @@ mod tests {
 
     #[test]
     fn complete_command_with_pipe_start() {
-        let mut cmd = Command::new(get_cargo_bin("ding"));
+        let mut cmd = ding();
         let cmd = cmd
             .arg("--spec")
             .arg("tests/petstore.yaml")
@@ -219,7 +256,7 @@ mod tests {
 
     #[test]
     fn complete_command_with_pipe_start_and_end() {
-        let mut cmd = Command::new(get_cargo_bin("ding"));
+        let mut cmd = ding();
         let cmd = cmd
             .arg("--spec")
             .arg("tests/petstore.yaml")
@@ -244,7 +281,7 @@ mod tests {
 
     #[test]
     fn json_complete_query_command() {
-        let mut cmd = Command::new(get_cargo_bin("ding"));
+        let mut cmd = ding();
         let cmd = cmd
             .arg("--spec")
             .arg("tests/petstore.yaml")
@@ -270,7 +307,7 @@ mod tests {
 
     #[test]
     fn json_alternates_query_command() {
-        let mut cmd = Command::new(get_cargo_bin("ding"));
+        let mut cmd = ding();
         let cmd = cmd
             .arg("--spec")
             .arg("tests/petstore.yaml")
@@ -322,7 +359,7 @@ mod tests {
 
     #[test]
     fn json_complete_header_command() {
-        let mut cmd = Command::new(get_cargo_bin("ding"));
+        let mut cmd = ding();
         let cmd = cmd
             .arg("--spec")
             .arg("tests/petstore.yaml")
@@ -348,7 +385,7 @@ mod tests {
 
     #[test]
     fn json_alternates_header_command() {
-        let mut cmd = Command::new(get_cargo_bin("ding"));
+        let mut cmd = ding();
         let cmd = cmd
             .arg("--spec")
             .arg("tests/petstore.yaml")
@@ -399,7 +436,7 @@ mod tests {
 
     #[test]
     fn json_complete_request_body_command() {
-        let mut cmd = Command::new(get_cargo_bin("ding"));
+        let mut cmd = ding();
         let cmd = cmd
             .arg("--spec")
             .arg("tests/petstore.yaml")
@@ -425,7 +462,7 @@ mod tests {
 
     #[test]
     fn json_complete_query_parameter_with_path_parameter() {
-        let mut cmd = Command::new(get_cargo_bin("ding"));
+        let mut cmd = ding();
         let cmd = cmd
             .arg("--spec")
             .arg("tests/petstore.yaml")
@@ -451,7 +488,7 @@ mod tests {
 
     #[test]
     fn json_complete_query_parameter_with_path_parameter_nested() {
-        let mut cmd = Command::new(get_cargo_bin("ding"));
+        let mut cmd = ding();
         let cmd = cmd
             .arg("--spec")
             .arg("tests/petstore.yaml")
@@ -477,7 +514,7 @@ mod tests {
 
     #[test]
     fn complete_query_parameter_command_with_prefix() {
-        let mut cmd = Command::new(get_cargo_bin("ding"));
+        let mut cmd = ding();
         let cmd = cmd
             .arg("--spec")
             .arg("tests/petstore.yaml")
@@ -504,7 +541,7 @@ mod tests {
 
     #[test]
     fn complete_query_parameter_command_without_leading_slash_prefix() {
-        let mut cmd = Command::new(get_cargo_bin("ding"));
+        let mut cmd = ding();
         let cmd = cmd
             .arg("--spec")
             .arg("tests/petstore.yaml")
@@ -534,7 +571,7 @@ mod tests {
 
     #[test]
     fn complete_query_parameter_command_with_trailing_slash_prefix() {
-        let mut cmd = Command::new(get_cargo_bin("ding"));
+        let mut cmd = ding();
         let cmd = cmd
             .arg("--spec")
             .arg("tests/petstore.yaml")
@@ -561,4 +598,2973 @@ mod tests {
         let stderr_str = String::from_utf8_lossy(&output.stderr);
         insta::assert_snapshot!(stderr_str);
     }
+
+    #[test]
+    fn json_complete_path_parameter_placeholder() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .arg("--json")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET https://localhost:9000/pets/{petId}")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn json_complete_path_parameter_placeholder_nested() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .arg("--json")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET https://localhost:9000/pets/{petId}/owner")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn complete_path_parameter_trailing_slash() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET https://localhost:9000/pets/")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn json_complete_partial_path() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .arg("--json")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET https://localhost:9000/pets/123/ow")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn json_complete_partial_path_candidates() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .arg("--json")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET https://localhost:9000/pe")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn complete_only_defined_method() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X POST https://localhost:9000/pets/123")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn json_complete_method_candidates() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .arg("--json")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X DELETE https://localhost:9000/pets")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn complete_cookie_parameter() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET https://localhost:9000/profile")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn alternates_cookie_parameters() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET https://localhost:9000/profile -b 'session='")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn complete_all_parameters() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .arg("--all")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET https://localhost:9000/pets/{petId}/owner")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn complete_all_required_parameters() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .arg("--all-required")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET https://localhost:9000/profile")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn complete_form_urlencoded_body() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X POST https://localhost:9000/login -H 'Content-Type: application/x-www-form-urlencoded'")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn keeps_form_urlencoded_fields() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X POST https://localhost:9000/login -H 'Content-Type: application/x-www-form-urlencoded' --data-urlencode 'password=hunter2' --data-urlencode 'username=rover'")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn complete_xml_body() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(
+                    b"curl -X POST https://localhost:9000/login -H 'Content-Type: application/xml'",
+                )
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn complete_multipart_body() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X POST https://localhost:9000/pets/1/photo")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn complete_text_body() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X PUT https://localhost:9000/pets/1/notes")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn complete_request_body_from_external_reference() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/split/openapi.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X POST https://localhost:9000/pets")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn complete_request_body_from_nested_reference() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/split/openapi.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X POST https://localhost:9000/owners")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn complete_parameter_through_escaped_reference() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/split/openapi.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET https://localhost:9000/pets/1/friends")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn reference_cycle_error() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/split/openapi.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X POST https://localhost:9000/loop")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+
+        let stderr_str = String::from_utf8_lossy(&output.stderr);
+        insta::assert_snapshot!(stderr_str);
+    }
+
+    #[test]
+    fn external_reference_cycle_error() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/split/cycle.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X POST https://localhost:9000/pets")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+
+        let stderr_str = String::from_utf8_lossy(&output.stderr);
+        insta::assert_snapshot!(stderr_str);
+    }
+
+    #[test]
+    fn missing_components_error() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/split/no-components.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET https://localhost:9000/pets")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+
+        let stderr_str = String::from_utf8_lossy(&output.stderr);
+        insta::assert_snapshot!(stderr_str);
+    }
+
+    #[test]
+    fn complete_server_from_spec() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/servers.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET /pets")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn complete_server_by_index() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/servers.yaml")
+            .arg("--server")
+            .arg("1")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET /pets")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn complete_server_by_description() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/servers.yaml")
+            .arg("--server")
+            .arg("local")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET /v1/pets")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn complete_server_from_path() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/servers.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET /pets/1")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn complete_server_from_operation() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/servers.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET /owners")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn strip_server_base_path() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/servers.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET http://localhost:9000/v1/pets")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn unknown_server_error() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/servers.yaml")
+            .arg("--server")
+            .arg("staging")
+            .stdin(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET /pets")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+
+        let stderr_str = String::from_utf8_lossy(&output.stderr);
+        insta::assert_snapshot!(stderr_str);
+    }
+
+    #[test]
+    fn complete_bearer_auth() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/security.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET https://localhost:9000/pets")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn complete_bearer_auth_from_config() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/security.yaml")
+            .arg("--config")
+            .arg("tests/ding.toml")
+            .env("DING_TEST_TOKEN", "secret-token")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET https://localhost:9000/pets")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn complete_basic_auth() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/security.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X POST https://localhost:9000/pets")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn complete_basic_auth_from_config() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/security.yaml")
+            .arg("--config")
+            .arg("tests/ding.toml")
+            .env("DING_TEST_BASIC", "rover:hunter2")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X POST https://localhost:9000/pets")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn complete_public_operation() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/security.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET https://localhost:9000/health")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn complete_api_keys() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/security.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET https://localhost:9000/keys")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn complete_api_key_cookie() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/security.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET https://localhost:9000/session")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn keeps_existing_auth() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/security.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(
+                    b"curl -X GET https://localhost:9000/pets -H 'Authorization: Bearer abc'",
+                )
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn complete_command_with_quoted_pipes() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET https://localhost:9000/pets -H 'X-Filter: a|b' | jq '.[] | .name'")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn complete_command_in_command_list() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(
+                    b"cd /tmp && TOKEN=abc curl -X GET https://localhost:9000/pets; echo done",
+                )
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn complete_command_in_command_substitution() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"PETS=$(curl -X GET https://localhost:9000/pets) && echo \"$PETS\"")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn complete_command_with_line_continuations() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET \\\n  https://localhost:9000/pets \\\n  | jq .")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn json_complete_command_after_pipe() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .arg("--json")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"echo hi | curl -X GET https://localhost:9000/pets/")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn cursor_cycles_method() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .arg("--json")
+            .arg("--cursor")
+            .arg("9")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET https://localhost:9000/pets")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn cursor_cycles_empty_query_parameter() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .arg("--json")
+            .arg("--cursor")
+            .arg("58")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET https://localhost:9000/pets --data-urlencode 'limit=' --data-urlencode 'page=2'")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn cursor_keeps_filled_query_parameter() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .arg("--json")
+            .arg("--cursor")
+            .arg("82")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET https://localhost:9000/pets --data-urlencode 'limit=' --data-urlencode 'page=2'")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn cursor_completes_body_property() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .arg("--json")
+            .arg("--cursor")
+            .arg("55")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X POST https://localhost:9000/pets -d '{\"name\": \"\", \"tag\": \"x\"}'")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn cursor_completes_body() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .arg("--json")
+            .arg("--cursor")
+            .arg("45")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X POST https://localhost:9000/pets -d '{\"name\": \"Rex\"}'")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn cursor_selects_command() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .arg("--json")
+            .arg("--cursor")
+            .arg("70")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET https://localhost:9000/pets/ && curl -X GET https://localhost:9000/pets/")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn keeps_unmodeled_flags() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -sS --compressed -o pets.json --retry 3 -k -X GET https://localhost:9000/pets")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn keeps_argument_quoting() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -s -X POST 'https://localhost:9000/pets' -H 'Content-Type: application/json'")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn keeps_flags_on_continued_lines() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET https://localhost:9000/pets \\\n  --compressed \\\n  -o pets.json")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn quotes_parameter_values() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/quoting.yaml")
+            .arg("--all")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET https://localhost:9000/notes")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+
+        // The values survive being parsed again
+        let request = curl_parser::ParsedRequest::from_str(&output_str)
+            .expect("Failed to parse output as a curl command");
+        assert_eq!(request.data_url_encoded["limit"], "20");
+        assert_eq!(request.data_url_encoded["q"], "it's a \"test\" & $HOME");
+        assert_eq!(request.data_url_encoded["sort"], "title");
+        assert_eq!(request.headers["x-note"], "don't `run` $(this)");
+    }
+
+    #[test]
+    fn quotes_body() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/quoting.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X POST https://localhost:9000/notes")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+
+        // The body survives being parsed again
+        let request = curl_parser::ParsedRequest::from_str(&output_str)
+            .expect("Failed to parse output as a curl command");
+        let body: serde_json::Value =
+            serde_json::from_str(&request.body().expect("Missing body").to_string())
+                .expect("Body is not JSON");
+        assert_eq!(body["text"], "it's \"quoted\" $HOME \\ done");
+    }
+
+    #[test]
+    fn quotes_url() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/quoting.yaml")
+            .arg("--json")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET 'https://localhost:9000/no?a=1&b=2'")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn init_zsh() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("init")
+            .arg("zsh")
+            .arg("--")
+            .arg("--spec")
+            .arg("/specs/pet store.yaml")
+            .stdout(std::process::Stdio::piped());
+
+        let output = cmd.output().expect("Failed to run command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn init_bash() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("init")
+            .arg("bash")
+            .arg("--")
+            .arg("--spec")
+            .arg("/specs/pet store.yaml")
+            .stdout(std::process::Stdio::piped());
+
+        let output = cmd.output().expect("Failed to run command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn init_fish() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("init")
+            .arg("fish")
+            .arg("--")
+            .arg("--spec")
+            .arg("/specs/pet store.yaml")
+            .stdout(std::process::Stdio::piped());
+
+        let output = cmd.output().expect("Failed to run command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn widget_output() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .arg("--widget")
+            .arg("--cursor")
+            .arg("9")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET https://localhost:9000/pets | jq\n")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn candidates_for_paths() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .arg("--candidates")
+            .arg("--json")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl https://localhost:9000/pe")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn candidates_for_parameters() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .arg("--candidates")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET https://localhost:9000/pets")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout).replace('\0', "\n");
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn candidates_for_servers() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/servers.yaml")
+            .arg("--candidates")
+            .arg("--json")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl /pets")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn parameter_values_from_schemas() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/values.yaml")
+            .arg("--all")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET https://localhost:9000/events")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn cycles_enum_parameter() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/values.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET -G https://localhost:9000/events/sorted --data-urlencode 'order=asc'")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn cycles_enum_parameter_under_cursor() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/values.yaml")
+            .arg("--json")
+            .arg("--cursor")
+            .arg("70")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET -G https://localhost:9000/events/sorted --data-urlencode 'order=desc' -s")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    /// Serve the file at `path` over HTTP with an `ETag` and `cache_control`, sending the status
+    /// of every response to the receiver.
+    fn serve_spec(
+        path: &'static str,
+        cache_control: &'static str,
+    ) -> (String, std::sync::mpsc::Receiver<u16>) {
+        use std::io::BufRead;
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("Failed to bind");
+        let url = format!("http://{}/openapi.yaml", listener.local_addr().unwrap());
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let body = std::fs::read_to_string(path).expect("Failed to read spec");
+            for stream in listener.incoming() {
+                let mut stream = stream.expect("Failed to accept connection");
+                let mut headers = String::new();
+                let mut reader = std::io::BufReader::new(&stream);
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).expect("Failed to read request");
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    headers.push_str(&line.to_lowercase());
+                }
+                let (status, response) = if headers.contains("if-none-match: \"v1\"") {
+                    (
+                        304,
+                        format!(
+                            "HTTP/1.1 304 Not Modified\r\nETag: \"v1\"\r\nCache-Control: {}\r\n\r\n",
+                            cache_control
+                        ),
+                    )
+                } else {
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nCache-Control: {}\r\nContent-Length: {}\r\n\r\n{}",
+                        cache_control,
+                        body.len(),
+                        body
+                    );
+                    (200, response)
+                };
+                // Sent before the response, so it's there by the time ding exits
+                sender.send(status).expect("Failed to send status");
+                stream
+                    .write_all(response.as_bytes())
+                    .expect("Failed to write response");
+            }
+        });
+        (url, receiver)
+    }
+
+    #[test]
+    fn spec_from_url() {
+        let (url, statuses) = serve_spec("tests/petstore.yaml", "no-cache");
+        let cache = std::env::temp_dir().join(format!("ding-test-cache-{}", std::process::id()));
+        let mut outputs = Vec::new();
+        // The second run revalidates the cached copy, as the server asks
+        for _ in 0..2 {
+            let mut cmd = ding();
+            let cmd = cmd
+                .arg("--spec")
+                .arg(&url)
+                .env("XDG_CACHE_HOME", &cache)
+                .stdin(std::process::Stdio::piped())
+                .stdout(std::process::Stdio::piped());
+
+            let mut child = cmd.spawn().expect("Failed to spawn command");
+
+            // Write to stdin
+            if let Some(stdin) = child.stdin.take() {
+                let mut stdin = stdin;
+                stdin
+                    .write_all(b"curl -X GET https://localhost:9000/pets")
+                    .expect("Failed to write to stdin");
+            }
+            let output = child
+                .wait_with_output()
+                .expect("Failed to wait for command");
+            outputs.push(String::from_utf8_lossy(&output.stdout).to_string());
+        }
+        std::fs::remove_dir_all(&cache).expect("Failed to remove cache");
+
+        assert_eq!(statuses.try_iter().collect::<Vec<_>>(), vec![200, 304]);
+        assert_eq!(outputs[0], outputs[1]);
+        insta::assert_snapshot!(outputs[0]);
+    }
+
+    #[test]
+    fn spec_from_url_within_max_age() {
+        let (url, statuses) = serve_spec("tests/petstore.yaml", "max-age=3600");
+        let cache =
+            std::env::temp_dir().join(format!("ding-test-fresh-cache-{}", std::process::id()));
+        let mut outputs = Vec::new();
+        // The second run uses the cached copy without asking the server
+        for _ in 0..2 {
+            let mut cmd = ding();
+            let cmd = cmd
+                .arg("--spec")
+                .arg(&url)
+                .env("XDG_CACHE_HOME", &cache)
+                .stdin(std::process::Stdio::piped())
+                .stdout(std::process::Stdio::piped());
+
+            let mut child = cmd.spawn().expect("Failed to spawn command");
+
+            // Write to stdin
+            if let Some(stdin) = child.stdin.take() {
+                let mut stdin = stdin;
+                stdin
+                    .write_all(b"curl -X GET https://localhost:9000/pets")
+                    .expect("Failed to write to stdin");
+            }
+            let output = child
+                .wait_with_output()
+                .expect("Failed to wait for command");
+            outputs.push(String::from_utf8_lossy(&output.stdout).to_string());
+        }
+        std::fs::remove_dir_all(&cache).expect("Failed to remove cache");
+
+        assert_eq!(statuses.try_iter().collect::<Vec<_>>(), vec![200]);
+        assert_eq!(outputs[0], outputs[1]);
+    }
+
+    #[test]
+    fn spec_from_registry() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("petstore")
+            .arg("--config")
+            .arg("tests/ding.toml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET https://localhost:9000/pets")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn spec_from_host() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--config")
+            .arg("tests/hosts.toml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET https://us.petstore.test/api/v1/pets")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn spec_from_host_and_port() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--config")
+            .arg("tests/hosts.toml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET http://localhost:9100/notes")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn spec_from_unknown_host() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--config")
+            .arg("tests/hosts.toml")
+            .arg("--json")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET https://petstore.test/pets")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn config_show() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--config")
+            .arg("tests/hosts.toml")
+            .arg("--widget")
+            .arg("config")
+            .arg("show")
+            // These read the global configuration on purpose
+            .env_remove("DING_NO_IMPLICIT_CONFIG")
+            .env("XDG_CONFIG_HOME", "tests/config")
+            .stdout(std::process::Stdio::piped());
+
+        let output = cmd.output().expect("Failed to run command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn config_defaults() {
+        let mut cmd = ding();
+        let cmd = cmd
+            // These read the global configuration on purpose
+            .env_remove("DING_NO_IMPLICIT_CONFIG")
+            .env("XDG_CONFIG_HOME", "tests/config")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET https://localhost:9000/pets")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn cached_spec_follows_referenced_files() {
+        let directory =
+            std::env::temp_dir().join(format!("ding-test-split-{}", std::process::id()));
+        let spec_directory = directory.join("spec");
+        std::fs::create_dir_all(spec_directory.join("schemas")).expect("Failed to create spec");
+        for file in ["openapi.yaml", "parameters.yaml", "schemas/pet.yaml"] {
+            std::fs::copy(format!("tests/split/{}", file), spec_directory.join(file))
+                .expect("Failed to copy spec");
+        }
+        let run = || {
+            let mut cmd = ding();
+            let cmd = cmd
+                .arg("--spec")
+                .arg(spec_directory.join("openapi.yaml"))
+                .env("XDG_CACHE_HOME", directory.join("cache"))
+                .stdin(std::process::Stdio::piped())
+                .stdout(std::process::Stdio::piped());
+
+            let mut child = cmd.spawn().expect("Failed to spawn command");
+
+            // Write to stdin
+            if let Some(stdin) = child.stdin.take() {
+                let mut stdin = stdin;
+                stdin
+                    .write_all(b"curl -X GET https://localhost:9000/pets/1")
+                    .expect("Failed to write to stdin");
+            }
+            let output = child
+                .wait_with_output()
+                .expect("Failed to wait for command");
+            String::from_utf8_lossy(&output.stdout).to_string()
+        };
+
+        let first = run();
+        let cached = run();
+        let parameters = spec_directory.join("parameters.yaml");
+        let content = std::fs::read_to_string(&parameters).expect("Failed to read parameters");
+        std::fs::write(
+            &parameters,
+            content.replace("example: true", "example: false"),
+        )
+        .expect("Failed to write parameters");
+        let changed = run();
+        std::fs::remove_dir_all(&directory).expect("Failed to remove spec");
+
+        assert_eq!(first, cached);
+        insta::assert_snapshot!(format!("{}\n{}", first, changed));
+    }
+
+    #[test]
+    fn swagger_query_parameters() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/swagger.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl https://petstore.test/v2/pets")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn swagger_body() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/swagger.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X POST https://petstore.test/v2/pets")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn swagger_form() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/swagger.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X POST https://petstore.test/v2/pets/1/photos")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn swagger_shared_form() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/swagger.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X POST https://petstore.test/v2/pets/1/videos")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn swagger_server() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/swagger.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET /pe")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn openapi31_body() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/openapi31.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X POST https://localhost:9000/pets")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn openapi31_query_parameters() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/openapi31.yaml")
+            .arg("--all")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl https://localhost:9000/pets")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn openapi31_path_item_component() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/openapi31.yaml")
+            .arg("--all")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl https://localhost:9000/pets/7")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn json_spec_sniffed_from_content() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/sniffed.spec")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl https://localhost:9000/pets")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn json_spec_parse_error() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/invalid.json")
+            .arg("--json")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl https://localhost:9000/pets")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn lint_valid_command() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/lint.yaml")
+            .arg("lint")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X PUT https://localhost:9000/pets/1 -H 'X-Request-Id: 0123456789' -H 'Content-Type: application/json' -d '{\"name\": \"Rover\", \"age\": 3}\'")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(format!(
+            "exit code {:?}\n{}",
+            output.status.code(),
+            output_str
+        ));
+    }
+
+    #[test]
+    fn lint_without_curl_command() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/lint.yaml")
+            .arg("lint")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"echo hello | jq .")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(format!(
+            "exit code {:?}\n{}",
+            output.status.code(),
+            output_str
+        ));
+    }
+
+    #[test]
+    fn lint_parameters() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/lint.yaml")
+            .arg("lint")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET 'https://localhost:9000/pets?verbose=true&tags=dog,bird'")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(format!(
+            "exit code {:?}\n{}",
+            output.status.code(),
+            output_str
+        ));
+    }
+
+    #[test]
+    fn lint_parameter_values() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/lint.yaml")
+            .arg("lint")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X PUT https://localhost:9000/pets/rover -H 'X-Request-Id: 1234' -H 'Content-Type: application/json' -d '{\"name\": \"Rover\"}\'")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(format!(
+            "exit code {:?}\n{}",
+            output.status.code(),
+            output_str
+        ));
+    }
+
+    #[test]
+    fn lint_body() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/lint.yaml")
+            .arg("lint")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X PUT https://localhost:9000/pets/1 -H 'X-Request-Id: 0123456789' -H 'Content-Type: application/json' -d '{\"age\": -1, \"status\": \"lost\", \"tags\": [1], \"color\": \"red\"}\'")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(format!(
+            "exit code {:?}\n{}",
+            output.status.code(),
+            output_str
+        ));
+    }
+
+    #[test]
+    fn lint_content_type() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/lint.yaml")
+            .arg("lint")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X PUT https://localhost:9000/pets/1 -H 'X-Request-Id: 0123456789' -d '{\"name\": \"Rover\"}\'")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(format!(
+            "exit code {:?}\n{}",
+            output.status.code(),
+            output_str
+        ));
+    }
+
+    #[test]
+    fn lint_unknown_method() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/lint.yaml")
+            .arg("--json")
+            .arg("lint")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X DELETE https://localhost:9000/pets")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(format!(
+            "exit code {:?}\n{}",
+            output.status.code(),
+            output_str
+        ));
+    }
+
+    #[test]
+    fn complete_literal_bearer_auth_from_config() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/security.yaml")
+            .arg("--config")
+            .arg("tests/literal.toml")
+            .env("DING_TEST_TOKEN", "secret-token")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET https://localhost:9000/pets")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn keep_typed_auth_reference() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/security.yaml")
+            .arg("--config")
+            .arg("tests/ding.toml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X POST https://localhost:9000/pets -u \"$DING_TEST_BASIC\"")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn keep_backslash_newline_in_single_quotes() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(
+                    b"curl -X POST \\\n  https://localhost:9000/pets \\\n  -d 'first \\\nsecond'",
+                )
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn cursor_in_non_ascii_body() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .arg("--json")
+            .arg("--cursor")
+            .arg("57")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(
+                    "curl -X POST https://localhost:9000/pets -d '{\"name\":\"Zoë\"}'".as_bytes(),
+                )
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn widget_zsh_non_ascii_buffer() {
+        // What the zsh widget sends: `$CURSOR` counts characters and `<<<` adds a newline
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .arg("--widget")
+            .arg("--cursor")
+            .arg("29")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(
+                    "echo 'ding → ok' && curl -X GET https://localhost:9000/pets\n".as_bytes(),
+                )
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn widget_bash_non_ascii_buffer() {
+        // What the bash widget sends: `$READLINE_POINT` counts characters and `<<<` adds a newline
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .arg("--widget")
+            .arg("--cursor")
+            .arg("57")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(
+                    "curl -X POST https://localhost:9000/pets -d '{\"name\":\"Zoë\"}'\n".as_bytes(),
+                )
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn widget_fish_non_ascii_buffer() {
+        // What the fish widget sends: `commandline -C` counts characters and `commandline` prints a newline
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .arg("--widget")
+            .arg("--cursor")
+            .arg("60")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(
+                    "set -l note naïve; curl -X GET https://localhost:9000/pets/{petId}\n"
+                        .as_bytes(),
+                )
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn keep_option_like_body() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(
+                    b"curl -X POST https://localhost:9000/pets -d '-bname=rex' -H 'x-note: hi'",
+                )
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn config_ignored_without_implicit_config() {
+        let output = ding()
+            .env("XDG_CONFIG_HOME", "tests/config")
+            .output()
+            .expect("Failed to run command");
+        let output_str = String::from_utf8_lossy(&output.stderr);
+        insta::assert_snapshot!(format!(
+            "exit code {:?}\n{}",
+            output.status.code(),
+            output_str
+        ));
+    }
+
+    #[test]
+    fn widget_error_with_non_ascii_buffer() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .arg("--widget")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all("curl -X GET https://localhost:9000/ünknown".as_bytes())
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn lint_empty_input_with_config_error() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/lint.yaml")
+            .arg("--config")
+            .arg("tests/missing.toml")
+            .arg("--json")
+            .arg("lint")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let child = cmd.spawn().expect("Failed to spawn command");
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(format!(
+            "exit code {:?}\n{}",
+            output.status.code(),
+            output_str
+        ));
+    }
+
+    #[test]
+    fn keep_dollar_signs_next_to_auth_reference() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/security.yaml")
+            .arg("--config")
+            .arg("tests/ding.toml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X POST https://localhost:9000/pets -H 'X-Note: $DING_TEST_BASIC' -d '{\"$ref\": \"$DING_TEST_BASIC costs $5\"}'")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn keep_unparsed_options_with_values() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/petstore.yaml")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(
+                    b"curl -D h.txt --limit-rate 1k -r 0-99 -X GET https://localhost:9000/pets",
+                )
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn lint_encoded_values() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/lint.yaml")
+            .arg("lint")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(
+                    b"curl 'https://localhost:9000/pets?limit=10&status=in+stock&tags=c%61t'",
+                )
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(format!(
+            "exit code {:?}\n{}",
+            output.status.code(),
+            output_str
+        ));
+    }
+
+    #[test]
+    fn lint_encoded_form_body() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/lint.yaml")
+            .arg("lint")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(
+                    b"curl -X POST https://localhost:9000/adoptions -d 'name=Rex+Jr&status=s%6Fld'",
+                )
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(format!(
+            "exit code {:?}\n{}",
+            output.status.code(),
+            output_str
+        ));
+    }
+
+    #[test]
+    fn complete_all_required_path_item_parameters() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/values.yaml")
+            .arg("--all-required")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X GET https://localhost:9000/events/1/attendees")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn query_parameters_with_form_body() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/values.yaml")
+            .arg("--all-required")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X POST https://localhost:9000/events/1/attendees")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn keep_query_parameters_in_url_with_form_body() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/values.yaml")
+            .arg("--all-required")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl -X POST 'https://localhost:9000/events/1/attendees?notify=true&page=2' -H 'x-tenant: acme' --data-urlencode 'name=Ann Lee'")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
+
+    #[test]
+    fn structurally_invalid_spec() {
+        let mut cmd = ding();
+        let cmd = cmd
+            .arg("--spec")
+            .arg("tests/malformed.yaml")
+            .arg("--json")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn command");
+
+        // Write to stdin
+        if let Some(stdin) = child.stdin.take() {
+            let mut stdin = stdin;
+            stdin
+                .write_all(b"curl https://localhost:9000/pets")
+                .expect("Failed to write to stdin");
+        }
+        let output = child
+            .wait_with_output()
+            .expect("Failed to wait for command");
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        insta::assert_snapshot!(output_str);
+    }
 }